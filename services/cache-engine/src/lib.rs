@@ -2,30 +2,35 @@
 //!
 //! This module provides a lock-free, multi-level cache system with:
 //! - L1 cache: In-memory with LRU eviction
-//! - L2 cache: Redis-backed with compression (placeholder)
-//! - L3 cache: Persistent disk storage with memory mapping (placeholder)
+//! - L2 cache: Redis-backed, entries stored as uncompressed bincode
+//! - L3 cache: Persistent disk storage, zstd-compressed, loaded/saved as a single snapshot
 //!
 //! Performance characteristics:
 //! - L1 cache hits: ~5-10ns (lock-free)
-//! - L2 cache hits: ~100-200μs (Redis network) - placeholder
-//! - L3 cache hits: ~1-5ms (disk I/O) - placeholder
+//! - L2 cache hits: ~100-200μs (Redis network)
+//! - L3 cache hits: only paid once at startup (`restore`), not per-key
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
-use parking_lot::RwLock;
+use std::path::{Path, PathBuf};
+use std::collections::VecDeque;
+use parking_lot::Mutex;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use async_trait::async_trait;
+use redis::AsyncCommands;
 use tokio::task::JoinHandle;
+use tokio::sync::mpsc;
 use std::os::raw::{c_char, c_void};
 use std::ffi::{CStr, CString};
 use std::ptr;
 use twox_hash::XxHash64;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use std::hash::Hasher;
 
 /// Cache entry with metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize)]
 struct CacheEntry {
     /// Cached data
     data: Vec<u8>,
@@ -33,6 +38,11 @@ struct CacheEntry {
     created_at: DateTime<Utc>,
     /// TTL in seconds
     ttl_seconds: u64,
+    /// CLOCK/second-chance reference bit. L1 keeps its live bit on `L1StoredEntry` instead,
+    /// so entries handed out by L1 always carry it unset. Skipped when an entry is
+    /// serialized for L2/L3 and defaults to unset on the way back in.
+    #[serde(skip, default)]
+    referenced: AtomicBool,
 }
 
 impl CacheEntry {
@@ -42,6 +52,7 @@ impl CacheEntry {
             data,
             created_at: now,
             ttl_seconds,
+            referenced: AtomicBool::new(false),
         }
     }
 
@@ -54,14 +65,650 @@ impl CacheEntry {
     }
 }
 
+impl Clone for CacheEntry {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            created_at: self.created_at,
+            ttl_seconds: self.ttl_seconds,
+            referenced: AtomicBool::new(self.referenced.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Average chunk size target for content-defined chunking: a chunk boundary is declared once
+/// roughly 1-in-2^13 rolling-hash values match, i.e. ~8KiB on average.
+const CDC_MASK: u64 = (1 << 13) - 1;
+/// Minimum chunk size, so boundaries found right after the previous one don't produce
+/// pathologically tiny chunks.
+const CDC_MIN_SIZE: usize = 2 * 1024;
+/// Maximum chunk size, so a long run without a matching fingerprint can't grow unbounded.
+const CDC_MAX_SIZE: usize = 64 * 1024;
+/// Rolling hash window: the fingerprint at byte `i` is a hash of bytes `[i - WINDOW + 1, i]`
+/// relative to the current chunk's start, so insertions near the front of a value don't
+/// desynchronize every boundary after them the way a simple non-rolling hash would.
+const CDC_WINDOW: usize = 48;
+/// Multiplier for the polynomial rolling hash. Arithmetic is all wrapping `u64`, so this only
+/// needs to be odd to keep the low bits well-mixed.
+const CDC_BASE: u64 = 1_099_511_628_211; // FNV-1a prime, reused here as the rolling multiplier
+
+/// Split `data` into content-defined chunks using a FastCDC-style rolling hash: a boundary is
+/// declared wherever the rolling fingerprint's low bits match `CDC_MASK`, subject to
+/// `CDC_MIN_SIZE`/`CDC_MAX_SIZE` bounds. Because the fingerprint only depends on the last
+/// `CDC_WINDOW` bytes, inserting or deleting bytes in the middle of a value shifts at most the
+/// chunk(s) around the edit, leaving every other chunk's hash (and so its dedup hit) unchanged.
+fn chunk_content(data: &[u8]) -> Vec<&[u8]> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let mut window_pow = 1u64;
+    for _ in 0..CDC_WINDOW {
+        window_pow = window_pow.wrapping_mul(CDC_BASE);
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0usize;
+    let mut fingerprint = 0u64;
+
+    for i in 0..data.len() {
+        fingerprint = fingerprint.wrapping_mul(CDC_BASE).wrapping_add(data[i] as u64);
+
+        let len = i - start + 1;
+        if len > CDC_WINDOW {
+            let dropped = data[i - CDC_WINDOW] as u64;
+            fingerprint = fingerprint.wrapping_sub(dropped.wrapping_mul(window_pow));
+        }
+
+        if len >= CDC_MIN_SIZE && (fingerprint & CDC_MASK == 0 || len >= CDC_MAX_SIZE) {
+            chunks.push(&data[start..=i]);
+            start = i + 1;
+            fingerprint = 0;
+        }
+    }
+
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+
+    chunks
+}
+
+/// Incrementally chunk-and-dedup a value as its bytes arrive, instead of needing the whole
+/// value buffered up front like `chunk_content` does. Runs the same rolling-hash boundary rule,
+/// but only ever holds `CDC_WINDOW` bytes of rolling state plus the current in-progress chunk
+/// (bounded by `CDC_MAX_SIZE`) — so streaming a multi-megabyte value through `set_stream` never
+/// requires the cache to materialize the whole thing at once.
+struct StreamingChunker<'a> {
+    chunks: &'a ChunkStore,
+    pending: Vec<u8>,
+    fingerprint: u64,
+    window_pow: u64,
+    hashes: Vec<u64>,
+    total_len: usize,
+}
+
+impl<'a> StreamingChunker<'a> {
+    fn new(chunks: &'a ChunkStore) -> Self {
+        let mut window_pow = 1u64;
+        for _ in 0..CDC_WINDOW {
+            window_pow = window_pow.wrapping_mul(CDC_BASE);
+        }
+        Self {
+            chunks,
+            pending: Vec::new(),
+            fingerprint: 0,
+            window_pow,
+            hashes: Vec::new(),
+            total_len: 0,
+        }
+    }
+
+    /// Feed in the next piece of the value as it arrives.
+    fn push(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.pending.push(byte);
+            self.total_len += 1;
+            self.fingerprint = self.fingerprint.wrapping_mul(CDC_BASE).wrapping_add(byte as u64);
+
+            let len = self.pending.len();
+            if len > CDC_WINDOW {
+                let dropped = self.pending[len - 1 - CDC_WINDOW] as u64;
+                self.fingerprint = self.fingerprint.wrapping_sub(dropped.wrapping_mul(self.window_pow));
+            }
+
+            if len >= CDC_MIN_SIZE && (self.fingerprint & CDC_MASK == 0 || len >= CDC_MAX_SIZE) {
+                self.flush_pending();
+            }
+        }
+    }
+
+    fn flush_pending(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        self.hashes.push(self.chunks.insert(&self.pending));
+        self.pending.clear();
+        self.fingerprint = 0;
+    }
+
+    /// Flush any trailing partial chunk and return the ordered chunk hashes plus the total
+    /// number of bytes streamed in.
+    fn finish(mut self) -> (Vec<u64>, usize) {
+        self.flush_pending();
+        (self.hashes, self.total_len)
+    }
+}
+
+/// A cache value in either of two forms: fully materialized (`Bytes`) or delivered
+/// incrementally through a channel (`Stream`) — mirroring the bytes/byte-stream distinction
+/// used by streaming reverse-proxy caches. `get`/`set` work in terms of `Bytes`;
+/// `get_stream`/`set_stream` work in terms of `Stream`, so moving a multi-megabyte value
+/// through the cache never requires holding the whole thing in memory at once.
+pub enum CacheData {
+    Bytes(Vec<u8>),
+    Stream {
+        receiver: mpsc::Receiver<Vec<u8>>,
+        /// Total size in bytes, if known up front (e.g. from a `Content-Length` header). Lets
+        /// `set_stream` pre-evict for capacity before the stream finishes instead of only
+        /// being able to check afterwards.
+        size_hint: Option<usize>,
+    },
+}
+
+/// One physically-stored, content-addressed chunk. `refcount` is the number of live
+/// `L1StoredEntry`s referencing this chunk; it's dropped from the store once that reaches zero.
+struct ChunkSlot {
+    data: Arc<[u8]>,
+    refcount: AtomicUsize,
+}
+
+/// Shared pool of unique chunks backing L1's cross-key deduplication, keyed by each chunk's
+/// `XxHash64` content hash. Large values (model outputs, embeddings blobs) often share long
+/// runs of identical bytes across keys; storing each distinct chunk once instead of once per
+/// key can save substantial memory at the cost of a hash lookup and a refcount bump per chunk
+/// on every `set`.
+struct ChunkStore {
+    chunks: DashMap<u64, ChunkSlot>,
+}
+
+impl ChunkStore {
+    fn new() -> Self {
+        Self { chunks: DashMap::new() }
+    }
+
+    fn hash_chunk(bytes: &[u8]) -> u64 {
+        let mut hasher = XxHash64::with_seed(0);
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    /// Store `bytes` as a chunk if it isn't already present, otherwise bump its refcount.
+    /// Returns the chunk's content hash either way.
+    fn insert(&self, bytes: &[u8]) -> u64 {
+        let hash = Self::hash_chunk(bytes);
+        self.chunks
+            .entry(hash)
+            .and_modify(|slot| {
+                slot.refcount.fetch_add(1, Ordering::Relaxed);
+            })
+            .or_insert_with(|| ChunkSlot {
+                data: Arc::from(bytes),
+                refcount: AtomicUsize::new(1),
+            });
+        hash
+    }
+
+    fn get(&self, hash: u64) -> Option<Arc<[u8]>> {
+        self.chunks.get(&hash).map(|slot| slot.data.clone())
+    }
+
+    /// Decrement a chunk's refcount, dropping it from the store once nothing references it.
+    fn release(&self, hash: u64) {
+        let should_remove = self
+            .chunks
+            .get(&hash)
+            .is_some_and(|slot| slot.refcount.fetch_sub(1, Ordering::AcqRel) == 1);
+        if should_remove {
+            self.chunks.remove(&hash);
+        }
+    }
+
+    /// Total size of every unique chunk currently stored, i.e. the actual memory footprint
+    /// after dedup (as opposed to the logical, pre-dedup size tracked by `L1Tier::bytes_used`).
+    fn deduplicated_bytes(&self) -> usize {
+        self.chunks.iter().map(|slot| slot.data.len()).sum()
+    }
+
+    fn clear(&self) {
+        self.chunks.clear();
+    }
+}
+
+/// A single level of the cache hierarchy. `CacheEngine` iterates its configured tiers in
+/// order on `get` (falling through and populating earlier tiers on a later-tier hit) and
+/// writes through every tier on `set`/`delete`, so L1, L2, and a future L3 all plug into the
+/// same read/write-through logic instead of each needing bespoke handling.
+#[async_trait]
+trait CacheTier: Send + Sync {
+    async fn get(&self, key: &str) -> Option<CacheEntry>;
+    /// Returns the number of entries this tier evicted to make room for the write (always
+    /// `0` for tiers without a capacity bound, e.g. Redis).
+    async fn set(&self, key: &str, entry: CacheEntry) -> usize;
+    async fn delete(&self, key: &str) -> bool;
+}
+
+/// Capacity bounds enforced by `L1Tier`'s CLOCK/second-chance eviction. `None` in either
+/// field disables that particular cap, so the default is unbounded (matching this cache's
+/// pre-existing behavior).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct L1Capacity {
+    pub max_entries: Option<usize>,
+    pub max_bytes: Option<usize>,
+}
+
+/// An L1-resident value, content-defined-chunked into `ChunkStore` entries instead of held as
+/// one contiguous buffer: `chunk_hashes` is the ordered list needed to reassemble it on read.
+/// Dedup is scoped to L1 only — L2 (Redis) and L3 (the disk snapshot) are separate processes
+/// or separate runs of this one, so they store a plain `CacheEntry` with the full bytes rather
+/// than hashes into an L1 chunk pool that may not exist by the time they're read back.
+struct L1StoredEntry {
+    chunk_hashes: Vec<u64>,
+    logical_len: usize,
+    created_at: DateTime<Utc>,
+    ttl_seconds: u64,
+    referenced: AtomicBool,
+}
+
+impl L1StoredEntry {
+    fn is_expired(&self) -> bool {
+        if self.ttl_seconds == 0 {
+            return false; // No expiration
+        }
+        let elapsed = Utc::now().signed_duration_since(self.created_at);
+        elapsed.num_seconds() as u64 > self.ttl_seconds
+    }
+}
+
+/// L1 tier: in-memory, lock-free hashmap, bounded by `capacity` via CLOCK/second-chance
+/// eviction. `clock_ring` holds every live key in a circular queue standing in for the
+/// classic fixed-size array + cursor: eviction pops from the front, clears and requeues any
+/// key whose reference bit is set (the "second chance"), and evicts the first one found
+/// already clear — equivalent to advancing a cursor around a ring and evicting on a cold pass.
+/// Values are stored chunked in `chunks` rather than inline, so identical runs of bytes across
+/// different keys are only held in memory once; `bytes_used` still tracks logical (pre-dedup)
+/// size, since that's what `capacity.max_bytes` is meant to bound.
+struct L1Tier {
+    entries: DashMap<String, L1StoredEntry>,
+    chunks: ChunkStore,
+    capacity: L1Capacity,
+    clock_ring: Mutex<VecDeque<String>>,
+    bytes_used: AtomicUsize,
+}
+
+impl L1Tier {
+    fn new(capacity: L1Capacity) -> Self {
+        Self {
+            entries: DashMap::new(),
+            chunks: ChunkStore::new(),
+            capacity,
+            clock_ring: Mutex::new(VecDeque::new()),
+            bytes_used: AtomicUsize::new(0),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn bytes_used(&self) -> usize {
+        self.bytes_used.load(Ordering::Relaxed)
+    }
+
+    /// Actual memory footprint of L1's chunk pool after dedup, for `CacheStats::l1_deduplicated_bytes`.
+    fn deduplicated_bytes(&self) -> usize {
+        self.chunks.deduplicated_bytes()
+    }
+
+    /// Evict one entry per the CLOCK/second-chance policy. Returns `true` if an entry was
+    /// evicted, `false` if the ring (and therefore the cache) is empty.
+    fn evict_one(&self) -> bool {
+        let mut ring = self.clock_ring.lock();
+        loop {
+            let Some(key) = ring.pop_front() else { return false };
+            let Some(entry) = self.entries.get(&key) else {
+                // Stale ring entry: already removed via `delete` or TTL sweep.
+                continue;
+            };
+            if entry.referenced.swap(false, Ordering::AcqRel) {
+                drop(entry);
+                ring.push_back(key);
+                continue;
+            }
+            drop(entry);
+            drop(ring);
+
+            if let Some((_, removed)) = self.entries.remove(&key) {
+                self.bytes_used.fetch_sub(removed.logical_len, Ordering::Relaxed);
+                for hash in &removed.chunk_hashes {
+                    self.chunks.release(*hash);
+                }
+            }
+            return true;
+        }
+    }
+
+    /// Reassemble a stored entry's chunks back into a plain `CacheEntry`, without touching its
+    /// reference bit — used both by `get_sync` (which does set it, to record the access) and
+    /// by callers like `flush` that read every entry without that counting as a cache hit.
+    fn reassemble(&self, stored: &L1StoredEntry) -> Option<CacheEntry> {
+        let mut data = Vec::with_capacity(stored.logical_len);
+        for hash in &stored.chunk_hashes {
+            data.extend_from_slice(&self.chunks.get(*hash)?);
+        }
+
+        Some(CacheEntry {
+            data,
+            created_at: stored.created_at,
+            ttl_seconds: stored.ttl_seconds,
+            referenced: AtomicBool::new(false),
+        })
+    }
+
+    /// Reassemble a key's chunks back into a plain `CacheEntry`. This is the synchronous core
+    /// of `CacheTier::get`: chunk lookups never touch the network, so there's no need for
+    /// `flush`/`restore`/the cleanup sweep/the raw FFI functions to go through an `async fn`
+    /// just to reach it.
+    fn get_sync(&self, key: &str) -> Option<CacheEntry> {
+        let stored = self.entries.get(key)?;
+        stored.referenced.store(true, Ordering::Relaxed);
+        self.reassemble(&stored)
+    }
+
+    /// Synchronous core of `CacheTier::set`: chunk `entry.data`, dedup the chunks into
+    /// `chunks`, and hand off to `finalize_set`. Returns the number of entries evicted to make
+    /// room.
+    fn set_sync(&self, key: &str, entry: CacheEntry) -> usize {
+        let new_len = entry.data.len();
+        let chunk_hashes: Vec<u64> = chunk_content(&entry.data)
+            .into_iter()
+            .map(|chunk| self.chunks.insert(chunk))
+            .collect();
+        self.finalize_set(key, chunk_hashes, new_len, entry.created_at, entry.ttl_seconds)
+    }
+
+    /// Stream-chunk and store a value without requiring the whole buffer in memory at once:
+    /// feed `chunks` through a `StreamingChunker` as each piece arrives, then finalize exactly
+    /// like `set_sync`. If `size_hint` and `capacity.max_bytes` are both known and this is a
+    /// new key, pre-evicts using the hint so a large incoming value doesn't transiently blow
+    /// past the cap while its chunks are still arriving.
+    async fn set_stream_sync(
+        &self,
+        key: &str,
+        mut chunks: mpsc::Receiver<Vec<u8>>,
+        size_hint: Option<usize>,
+        ttl_seconds: u64,
+    ) -> usize {
+        let mut evicted = 0;
+
+        if let (Some(max_bytes), Some(hint)) = (self.capacity.max_bytes, size_hint) {
+            if self.entries.get(key).is_none() {
+                while self.bytes_used.load(Ordering::Relaxed) + hint > max_bytes {
+                    if self.evict_one() {
+                        evicted += 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let created_at = Utc::now();
+        let mut chunker = StreamingChunker::new(&self.chunks);
+        while let Some(piece) = chunks.recv().await {
+            chunker.push(&piece);
+        }
+        let (chunk_hashes, logical_len) = chunker.finish();
+
+        evicted + self.finalize_set(key, chunk_hashes, logical_len, created_at, ttl_seconds)
+    }
+
+    /// Shared tail of `set_sync`/`set_stream_sync`: evict to make room (if needed), release
+    /// the overwritten entry's chunks (if any), and insert the new one.
+    ///
+    /// The overwritten entry (if any) is removed from `entries` up front, not just read, so
+    /// that the `max_bytes` eviction pass below can't pick the same key back up via
+    /// `evict_one` and double-release its chunks / double-subtract its bytes. `clock_ring` is
+    /// only pushed to on first insert: an overwrite's key is already sitting in the ring from
+    /// whenever it was first inserted, and `evict_one` re-resolves each ring entry against
+    /// `entries` when it's popped, so that original slot still finds (and can evict) the
+    /// current entry. Pushing again on every overwrite would grow the ring without bound for a
+    /// repeatedly-set key and do nothing else useful. The one exception: the `max_bytes` pass
+    /// can pop an overwritten key's slot and discard it as stale (its entry is already out of
+    /// `entries`), so after that pass runs on an overwrite the key is requeued if it's missing.
+    fn finalize_set(
+        &self,
+        key: &str,
+        chunk_hashes: Vec<u64>,
+        logical_len: usize,
+        created_at: DateTime<Utc>,
+        ttl_seconds: u64,
+    ) -> usize {
+        let mut evicted = 0;
+
+        let is_new = match self.entries.remove(key) {
+            Some((_, old)) => {
+                self.bytes_used.fetch_sub(old.logical_len, Ordering::Relaxed);
+                for hash in &old.chunk_hashes {
+                    self.chunks.release(*hash);
+                }
+                false
+            }
+            None => true,
+        };
+
+        if is_new {
+            if let Some(max_entries) = self.capacity.max_entries {
+                while self.entries.len() >= max_entries {
+                    if self.evict_one() {
+                        evicted += 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut bytes_pass_ran = false;
+        if let Some(max_bytes) = self.capacity.max_bytes {
+            while self.bytes_used.load(Ordering::Relaxed) + logical_len > max_bytes {
+                bytes_pass_ran = true;
+                if self.evict_one() {
+                    evicted += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        if is_new {
+            self.clock_ring.lock().push_back(key.to_string());
+        } else if bytes_pass_ran {
+            let mut ring = self.clock_ring.lock();
+            if !ring.iter().any(|k| k == key) {
+                ring.push_back(key.to_string());
+            }
+        }
+        self.bytes_used.fetch_add(logical_len, Ordering::Relaxed);
+        self.entries.insert(
+            key.to_string(),
+            L1StoredEntry {
+                chunk_hashes,
+                logical_len,
+                created_at,
+                ttl_seconds,
+                referenced: AtomicBool::new(false),
+            },
+        );
+        evicted
+    }
+
+    /// Ordered chunk hashes for a live, non-expired key, without reassembling them into one
+    /// buffer — used by `CacheEngine::get_stream` so a caller can read chunks one at a time.
+    fn chunk_hashes_for(&self, key: &str) -> Option<Vec<u64>> {
+        let stored = self.entries.get(key)?;
+        if stored.is_expired() {
+            return None;
+        }
+        stored.referenced.store(true, Ordering::Relaxed);
+        Some(stored.chunk_hashes.clone())
+    }
+
+    /// Total logical length of a set of chunks, or `None` if any of them have since been
+    /// evicted from under us (e.g. a racing `delete` freed the last reference).
+    fn chunk_total_len(&self, hashes: &[u64]) -> Option<usize> {
+        hashes.iter().map(|hash| self.chunks.get(*hash).map(|bytes| bytes.len())).sum()
+    }
+
+    fn chunk_bytes(&self, hash: u64) -> Option<Arc<[u8]>> {
+        self.chunks.get(hash)
+    }
+
+    /// Synchronous core of `CacheTier::delete`.
+    fn delete_sync(&self, key: &str) -> bool {
+        match self.entries.remove(key) {
+            Some((_, removed)) => {
+                self.bytes_used.fetch_sub(removed.logical_len, Ordering::Relaxed);
+                for hash in &removed.chunk_hashes {
+                    self.chunks.release(*hash);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop every entry and release every chunk, resetting the tier to empty. The clock ring
+    /// is left untouched: `evict_one` already tolerates ring entries whose key has since been
+    /// removed, treating them as stale and skipping past them.
+    fn clear(&self) {
+        self.entries.clear();
+        self.chunks.clear();
+        self.bytes_used.store(0, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl CacheTier for L1Tier {
+    async fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.get_sync(key)
+    }
+
+    async fn set(&self, key: &str, entry: CacheEntry) -> usize {
+        self.set_sync(key, entry)
+    }
+
+    async fn delete(&self, key: &str) -> bool {
+        self.delete_sync(key)
+    }
+}
+
+/// L2 tier: Redis-backed, shared across processes. `CacheEntry` is bincode-serialized so the
+/// same `created_at`/`ttl_seconds` metadata governs expiry whether the entry is read back
+/// from L1 or L2. Every method swallows connection/protocol errors and reports a miss (or a
+/// no-op write) instead of propagating them, so a Redis outage degrades the engine to
+/// L1-only rather than failing calls.
+struct RedisTier {
+    client: redis::Client,
+}
+
+impl RedisTier {
+    fn new(redis_url: &str) -> redis::RedisResult<Self> {
+        Ok(Self { client: redis::Client::open(redis_url)? })
+    }
+}
+
+#[async_trait]
+impl CacheTier for RedisTier {
+    async fn get(&self, key: &str) -> Option<CacheEntry> {
+        let mut conn = self.client.get_multiplexed_async_connection().await.ok()?;
+        let bytes: Vec<u8> = conn.get(key).await.ok()?;
+        if bytes.is_empty() {
+            return None;
+        }
+
+        let entry: CacheEntry = bincode::deserialize(&bytes).ok()?;
+        if entry.is_expired() {
+            return None;
+        }
+        Some(entry)
+    }
+
+    async fn set(&self, key: &str, entry: CacheEntry) -> usize {
+        let Ok(mut conn) = self.client.get_multiplexed_async_connection().await else { return 0 };
+        let Ok(bytes) = bincode::serialize(&entry) else { return 0 };
+
+        let result: redis::RedisResult<()> = if entry.ttl_seconds == 0 {
+            conn.set(key, bytes).await
+        } else {
+            conn.set_ex(key, bytes, entry.ttl_seconds).await
+        };
+        let _ = result;
+        // Redis has no capacity-based eviction of its own, so it never reports evictions.
+        0
+    }
+
+    async fn delete(&self, key: &str) -> bool {
+        let Ok(mut conn) = self.client.get_multiplexed_async_connection().await else { return false };
+        conn.del::<_, i64>(key).await.unwrap_or(0) > 0
+    }
+}
+
+/// On-disk snapshot format written by `CacheEngine::flush` and read by
+/// `CacheEngine::restore`: every still-live L1 entry at save time, bincode-serialized and
+/// then zstd-compressed as a whole. Unlike L1/L2, this isn't a per-key online tier — it's a
+/// bulk snapshot taken on shutdown and replayed on startup, so it doesn't implement
+/// `CacheTier` or participate in the per-call `get`/`set`/`delete` fallthrough.
+#[derive(Serialize, Deserialize)]
+struct DiskSnapshot {
+    entries: Vec<(String, CacheEntry)>,
+}
+
+/// zstd compression level used by `flush` when the engine wasn't given a specific one.
+const DEFAULT_L3_COMPRESSION_LEVEL: i32 = 3;
+
 /// Multi-level cache engine
 pub struct CacheEngine {
-    /// L1 cache - in-memory, lock-free hashmap
-    l1_cache: DashMap<String, CacheEntry>,
-    /// Statistics
-    stats: Arc<RwLock<CacheStats>>,
-    /// Phantom data for async compatibility
-    _cleanup_handle: std::marker::PhantomData<()>,
+    /// L1 cache - in-memory, lock-free hashmap. `Arc`-wrapped so the background cleanup
+    /// task spawned by `with_cleanup`/`spawn_cleanup` can share it without borrowing `self`.
+    l1: Arc<L1Tier>,
+    /// Optional L2 cache - Redis-backed, shared across processes. `None` when no Redis URL
+    /// was configured, in which case the engine behaves exactly as L1-only.
+    l2: Option<RedisTier>,
+    /// Path `flush`/`restore` read and write the L3 snapshot at. `None` disables
+    /// persistence entirely, making both methods no-ops.
+    l3_path: Option<PathBuf>,
+    /// zstd compression level `flush` encodes the snapshot with.
+    l3_compression_level: i32,
+    /// Counters
+    counters: Arc<CacheCounters>,
+    /// Background task spawned by `spawn_cleanup` that periodically sweeps expired L1
+    /// entries. `None` when no cleanup task is running. Aborted on `Drop` so the task
+    /// doesn't outlive (and keep polling for) an engine nobody holds anymore.
+    cleanup_handle: Option<JoinHandle<()>>,
+}
+
+/// Lock-free counters backing `CacheStats`. Bumped on the `get`/`set` hot paths, so these are
+/// plain `AtomicU64`s rather than fields behind `stats`'s old `RwLock` — a write lock taken just
+/// to increment a counter serializes every cache operation against every other one for no reason.
+/// Gauge-like fields (`l1_size`/`l1_bytes`/`l1_deduplicated_bytes`) aren't tracked here at all;
+/// they're read live from `L1Tier` when a `CacheStats` snapshot is materialized.
+#[derive(Default)]
+struct CacheCounters {
+    l1_hits: AtomicU64,
+    l1_misses: AtomicU64,
+    evictions: AtomicU64,
+    total_operations: AtomicU64,
 }
 
 /// Cache statistics
@@ -72,75 +719,328 @@ pub struct CacheStats {
     pub evictions: u64,
     pub total_operations: u64,
     pub l1_size: usize,
+    /// Logical (pre-dedup) bytes across all L1 values, the same total that existed before
+    /// chunking: what `max_bytes` is measured against.
+    pub l1_bytes: usize,
+    /// Actual bytes held in L1's shared chunk pool after cross-key dedup. Will be <= `l1_bytes`,
+    /// and strictly less whenever two or more keys share identical chunks.
+    pub l1_deduplicated_bytes: usize,
+}
+
+impl CacheStats {
+    /// Render this snapshot in Prometheus text exposition format: counters for hits, misses,
+    /// evictions, and total operations; gauges for L1 size and byte usage; and a derived hit
+    /// ratio gauge, so operators can scrape the cache the way the garage/mirror-cache stacks
+    /// expose their metrics.
+    pub fn render_prometheus(&self) -> String {
+        let total_lookups = self.l1_hits + self.l1_misses;
+        let hit_ratio = if total_lookups > 0 { self.l1_hits as f64 / total_lookups as f64 } else { 0.0 };
+
+        let mut out = String::new();
+
+        out.push_str("# HELP cache_l1_hits_total Total L1 cache hits.\n");
+        out.push_str("# TYPE cache_l1_hits_total counter\n");
+        out.push_str(&format!("cache_l1_hits_total {}\n", self.l1_hits));
+
+        out.push_str("# HELP cache_l1_misses_total Total L1 cache misses.\n");
+        out.push_str("# TYPE cache_l1_misses_total counter\n");
+        out.push_str(&format!("cache_l1_misses_total {}\n", self.l1_misses));
+
+        out.push_str("# HELP cache_evictions_total Total entries evicted from L1.\n");
+        out.push_str("# TYPE cache_evictions_total counter\n");
+        out.push_str(&format!("cache_evictions_total {}\n", self.evictions));
+
+        out.push_str("# HELP cache_operations_total Total cache operations processed.\n");
+        out.push_str("# TYPE cache_operations_total counter\n");
+        out.push_str(&format!("cache_operations_total {}\n", self.total_operations));
+
+        out.push_str("# HELP cache_l1_size Current number of entries held in L1.\n");
+        out.push_str("# TYPE cache_l1_size gauge\n");
+        out.push_str(&format!("cache_l1_size {}\n", self.l1_size));
+
+        out.push_str("# HELP cache_l1_bytes Current logical bytes stored in L1, before dedup.\n");
+        out.push_str("# TYPE cache_l1_bytes gauge\n");
+        out.push_str(&format!("cache_l1_bytes {}\n", self.l1_bytes));
+
+        out.push_str("# HELP cache_l1_deduplicated_bytes Current physical bytes in L1's shared chunk pool.\n");
+        out.push_str("# TYPE cache_l1_deduplicated_bytes gauge\n");
+        out.push_str(&format!("cache_l1_deduplicated_bytes {}\n", self.l1_deduplicated_bytes));
+
+        out.push_str("# HELP cache_l1_hit_ratio Derived L1 hit ratio: hits / (hits + misses).\n");
+        out.push_str("# TYPE cache_l1_hit_ratio gauge\n");
+        out.push_str(&format!("cache_l1_hit_ratio {hit_ratio}\n"));
+
+        out
+    }
 }
 
 impl CacheEngine {
-    /// Create a new cache engine
+    /// Create a new cache engine with L1 only and no capacity bound (matching this cache's
+    /// historical unbounded behavior).
     pub fn new() -> Self {
+        Self::with_capacity(L1Capacity::default())
+    }
+
+    /// Create a new cache engine with L1 only, bounded by `capacity`. Once `max_entries` or
+    /// `max_bytes` is reached, further writes evict via CLOCK/second-chance before inserting.
+    pub fn with_capacity(capacity: L1Capacity) -> Self {
         Self {
-            l1_cache: DashMap::new(),
-            stats: Arc::new(RwLock::new(CacheStats::default())),
-            _cleanup_handle: std::marker::PhantomData,
+            l1: Arc::new(L1Tier::new(capacity)),
+            l2: None,
+            l3_path: None,
+            l3_compression_level: DEFAULT_L3_COMPRESSION_LEVEL,
+            counters: Arc::new(CacheCounters::default()),
+            cleanup_handle: None,
         }
     }
 
-    /// Get a value from cache
-    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
-        let mut stats = self.stats.write();
-        stats.total_operations += 1;
-        drop(stats);
+    /// Create a new cache engine with an L2 Redis tier in front of an unbounded L1. Connection
+    /// failures at construction time (e.g. a malformed URL) are reported as an error; transient
+    /// failures once running degrade gracefully per-call instead.
+    pub fn new_with_redis(redis_url: &str) -> redis::RedisResult<Self> {
+        Ok(Self {
+            l1: Arc::new(L1Tier::new(L1Capacity::default())),
+            l2: Some(RedisTier::new(redis_url)?),
+            l3_path: None,
+            l3_compression_level: DEFAULT_L3_COMPRESSION_LEVEL,
+            counters: Arc::new(CacheCounters::default()),
+            cleanup_handle: None,
+        })
+    }
+
+    /// Create a new cache engine with a background task that sweeps expired L1 entries
+    /// every `interval`, instead of relying purely on lazy expiration at `get` time. Bounds
+    /// memory usage over long-running sessions where many keys are written once and never
+    /// read again.
+    pub fn with_cleanup(interval: Duration) -> Self {
+        let mut engine = Self::new();
+        engine.spawn_cleanup(interval);
+        engine
+    }
+
+    /// Spawn (or replace) the background cleanup task on an existing engine. The previous
+    /// task, if any, is aborted first.
+    pub fn spawn_cleanup(&mut self, interval: Duration) {
+        if let Some(handle) = self.cleanup_handle.take() {
+            handle.abort();
+        }
+
+        let l1 = Arc::clone(&self.l1);
+        let counters = Arc::clone(&self.counters);
+
+        self.cleanup_handle = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+
+                let expired: Vec<String> = l1
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.value().is_expired())
+                    .map(|entry| entry.key().clone())
+                    .collect();
+
+                if expired.is_empty() {
+                    continue;
+                }
+
+                for key in &expired {
+                    l1.delete_sync(key);
+                }
+
+                counters.evictions.fetch_add(expired.len() as u64, Ordering::Relaxed);
+            }
+        }));
+    }
+
+    /// Create a new cache engine backed by a persistent L3 snapshot at `path`, immediately
+    /// restoring it into L1 if it exists so the cache is warm across process restarts
+    /// instead of cold-starting every run.
+    pub fn new_with_persistence(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut engine = Self::new();
+        engine.l3_path = Some(path.as_ref().to_path_buf());
+        engine.restore()?;
+        Ok(engine)
+    }
+
+    /// Serialize every still-live L1 entry to the configured L3 path, zstd-compressed.
+    /// Entries already expired at save time are skipped so they never resurrect on the next
+    /// `restore`. A no-op if persistence wasn't configured.
+    pub fn flush(&self) -> std::io::Result<()> {
+        let Some(path) = &self.l3_path else { return Ok(()) };
+
+        let entries: Vec<(String, CacheEntry)> = self
+            .l1
+            .entries
+            .iter()
+            .filter(|entry| !entry.value().is_expired())
+            .filter_map(|entry| {
+                let reassembled = self.l1.reassemble(entry.value())?;
+                Some((entry.key().clone(), reassembled))
+            })
+            .collect();
+
+        let serialized = bincode::serialize(&DiskSnapshot { entries })
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        let compressed = zstd::stream::encode_all(&serialized[..], self.l3_compression_level)?;
+
+        std::fs::write(path, compressed)
+    }
+
+    /// Load the L3 snapshot at the configured path back into L1, re-checking expiry so
+    /// entries that went stale while the process was down aren't resurrected. A no-op if
+    /// persistence wasn't configured or no snapshot file exists yet.
+    pub fn restore(&self) -> std::io::Result<()> {
+        let Some(path) = &self.l3_path else { return Ok(()) };
+        if !path.exists() {
+            return Ok(());
+        }
 
-        // Try L1 cache first (lock-free)
-        if let Some(entry) = self.l1_cache.get(key) {
+        let compressed = std::fs::read(path)?;
+        let decompressed = zstd::stream::decode_all(&compressed[..])?;
+        let snapshot: DiskSnapshot = bincode::deserialize(&decompressed)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        for (key, entry) in snapshot.entries {
             if !entry.is_expired() {
-                let entry_data = entry.data.clone();
+                self.l1.set_sync(&key, entry);
+            }
+        }
 
-                let mut stats = self.stats.write();
-                stats.l1_hits += 1;
-                return Some(entry_data);
+        Ok(())
+    }
+
+    /// Get a value from cache, falling through L1 to L2 on a miss and repopulating L1 when
+    /// L2 satisfies the read.
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.counters.total_operations.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(entry) = self.l1.get(key).await {
+            if !entry.is_expired() {
+                self.counters.l1_hits.fetch_add(1, Ordering::Relaxed);
+                return Some(entry.data);
             } else {
-                // Entry expired, remove it
-                self.l1_cache.remove(key);
+                self.l1.delete(key).await;
+            }
+        }
+
+        self.counters.l1_misses.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(l2) = &self.l2 {
+            if let Some(entry) = l2.get(key).await {
+                let data = entry.data.clone();
+                let evicted = self.l1.set(key, entry).await;
+                self.counters.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
+                return Some(data);
             }
         }
 
-        // L1 miss
-        let mut stats = self.stats.write();
-        stats.l1_misses += 1;
         None
     }
 
-    /// Set a value in cache
+    /// Set a value in cache, writing through to L2 when configured.
     pub async fn set(&self, key: &str, value: Vec<u8>, ttl_seconds: u64) {
         let entry = CacheEntry::new(value, ttl_seconds);
-        self.l1_cache.insert(key.to_string(), entry);
+        let evicted = self.l1.set(key, entry.clone()).await;
+        if let Some(l2) = &self.l2 {
+            l2.set(key, entry).await;
+        }
 
-        let mut stats = self.stats.write();
-        stats.l1_size = self.l1_cache.len();
+        self.counters.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
     }
 
-    /// Delete a value from cache
-    pub async fn delete(&self, key: &str) -> bool {
-        let existed = self.l1_cache.remove(key).is_some();
+    /// Write a value into the cache either all at once (`CacheData::Bytes`, equivalent to
+    /// `set`) or incrementally (`CacheData::Stream`). The streaming path chunks and dedups as
+    /// bytes arrive, so a multi-megabyte value never needs to be fully buffered first.
+    /// Streamed writes are L1-only: L2/Redis only ever stores one contiguous blob, and
+    /// reassembling one here just to write through would defeat the point of streaming.
+    pub async fn set_stream(&self, key: &str, data: CacheData, ttl_seconds: u64) {
+        match data {
+            CacheData::Bytes(bytes) => self.set(key, bytes, ttl_seconds).await,
+            CacheData::Stream { receiver, size_hint } => {
+                let evicted = self.l1.set_stream_sync(key, receiver, size_hint, ttl_seconds).await;
+                self.counters.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Read a value back as a stream of its stored chunks rather than one reassembled buffer,
+    /// so a caller piping a large cached response elsewhere doesn't have to hold the whole
+    /// thing in memory either. L1-only: unlike `get`, this does not fall through to L2 on a
+    /// miss, since Redis only ever has one contiguous blob to hand back anyway.
+    pub async fn get_stream(&self, key: &str) -> Option<CacheData> {
+        self.counters.total_operations.fetch_add(1, Ordering::Relaxed);
+
+        let Some(chunk_hashes) = self.l1.chunk_hashes_for(key) else {
+            self.counters.l1_misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
 
-        let mut stats = self.stats.write();
-        stats.l1_size = self.l1_cache.len();
+        self.counters.l1_hits.fetch_add(1, Ordering::Relaxed);
+
+        let size_hint = self.l1.chunk_total_len(&chunk_hashes);
+        let (tx, rx) = mpsc::channel(8);
+        let l1 = Arc::clone(&self.l1);
+
+        tokio::spawn(async move {
+            for hash in chunk_hashes {
+                let Some(bytes) = l1.chunk_bytes(hash) else { break };
+                if tx.send(bytes.to_vec()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Some(CacheData::Stream { receiver: rx, size_hint })
+    }
+
+    /// Delete a value from cache, propagating to L2 when configured.
+    pub async fn delete(&self, key: &str) -> bool {
+        let existed = self.l1.delete(key).await;
+        if let Some(l2) = &self.l2 {
+            l2.delete(key).await;
+        }
 
         existed
     }
 
-    /// Clear all cache entries
+    /// Clear all L1 cache entries. L2 is left untouched, since flushing shared Redis state
+    /// is a more destructive operation than clearing a process-local cache.
     pub async fn clear(&self) {
-        self.l1_cache.clear();
-
-        let mut stats = self.stats.write();
-        stats.l1_size = 0;
+        self.l1.clear();
     }
 
-    /// Get cache statistics
+    /// Get cache statistics. The hit/miss/eviction/operation counters are live atomics;
+    /// the size/byte gauges are read straight from L1 rather than cached alongside them.
     pub async fn get_stats(&self) -> CacheStats {
-        let stats = self.stats.read();
-        stats.clone()
+        self.stats_snapshot()
+    }
+
+    /// Sync core of `get_stats`, shared with the FFI surface and `render_metrics` so none of
+    /// them need `tokio`'s async machinery just to load a handful of atomics.
+    fn stats_snapshot(&self) -> CacheStats {
+        CacheStats {
+            l1_hits: self.counters.l1_hits.load(Ordering::Relaxed),
+            l1_misses: self.counters.l1_misses.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+            total_operations: self.counters.total_operations.load(Ordering::Relaxed),
+            l1_size: self.l1.len(),
+            l1_bytes: self.l1.bytes_used(),
+            l1_deduplicated_bytes: self.l1.deduplicated_bytes(),
+        }
+    }
+
+    /// Render current stats in Prometheus text exposition format. See `CacheStats::render_prometheus`.
+    pub async fn render_metrics(&self) -> String {
+        self.stats_snapshot().render_prometheus()
+    }
+}
+
+impl Drop for CacheEngine {
+    fn drop(&mut self) {
+        if let Some(handle) = self.cleanup_handle.take() {
+            handle.abort();
+        }
     }
 }
 
@@ -153,6 +1053,26 @@ pub extern "C" fn cache_engine_new() -> *mut c_void {
     Box::into_raw(engine) as *mut c_void
 }
 
+/// Create a new cache engine backed by Redis as an L2 tier. Returns null if the connection
+/// URL couldn't be parsed.
+#[no_mangle]
+pub extern "C" fn cache_engine_new_with_redis(redis_url: *const c_char) -> *mut c_void {
+    if redis_url.is_null() {
+        return ptr::null_mut();
+    }
+
+    unsafe {
+        let Ok(url) = CStr::from_ptr(redis_url).to_str() else {
+            return ptr::null_mut();
+        };
+
+        match CacheEngine::new_with_redis(url) {
+            Ok(engine) => Box::into_raw(Box::new(engine)) as *mut c_void,
+            Err(_) => ptr::null_mut(),
+        }
+    }
+}
+
 /// Drop a cache engine
 #[no_mangle]
 pub extern "C" fn cache_engine_drop(engine: *mut c_void) {
@@ -179,11 +1099,11 @@ pub extern "C" fn cache_get(
         let engine = &*(engine as *mut CacheEngine);
         let key_str = CStr::from_ptr(key).to_str().unwrap_or("");
 
-        // This is a simplified synchronous version
+        // This is a simplified synchronous version that only checks L1
         // In production, we'd need async runtime integration
-        if let Some(entry) = engine.l1_cache.get(key_str) {
+        if let Some(entry) = engine.l1.get_sync(key_str) {
             if !entry.is_expired() {
-                let data = entry.value().data.clone();
+                let data = entry.data;
                 *value_len = data.len();
                 // Allocate memory for the output value
                 // Note: In production, caller should free this memory
@@ -217,7 +1137,7 @@ pub extern "C" fn cache_set(
 
         // Create cache entry
         let entry = CacheEntry::new(data, ttl_seconds);
-        engine.l1_cache.insert(key_str.to_string(), entry);
+        engine.l1.set_sync(key_str, entry);
 
         true
     }
@@ -237,7 +1157,7 @@ pub extern "C" fn cache_delete(
         let engine = &*(engine as *mut CacheEngine);
         let key_str = CStr::from_ptr(key).to_str().unwrap_or("");
 
-        engine.l1_cache.remove(key_str).is_some()
+        engine.l1.delete_sync(key_str)
     }
 }
 
@@ -250,7 +1170,7 @@ pub extern "C" fn cache_clear(engine: *mut c_void) -> bool {
 
     unsafe {
         let engine = &*(engine as *mut CacheEngine);
-        engine.l1_cache.clear();
+        engine.l1.clear();
         true
     }
 }
@@ -264,14 +1184,7 @@ pub extern "C" fn cache_get_stats(engine: *mut c_void) -> *const c_char {
 
     unsafe {
         let engine = &*(engine as *mut CacheEngine);
-
-        let stats = CacheStats {
-            l1_hits: 0,
-            l1_misses: 0,
-            evictions: 0,
-            total_operations: 0,
-            l1_size: engine.l1_cache.len(),
-        };
+        let stats = engine.stats_snapshot();
 
         match serde_json::to_string(&stats) {
             Ok(json) => {
@@ -283,6 +1196,53 @@ pub extern "C" fn cache_get_stats(engine: *mut c_void) -> *const c_char {
     }
 }
 
+/// Get cache statistics rendered in Prometheus text exposition format, suitable for serving
+/// directly from a `/metrics` endpoint.
+#[no_mangle]
+pub extern "C" fn cache_metrics_prometheus(engine: *mut c_void) -> *const c_char {
+    if engine.is_null() {
+        return ptr::null();
+    }
+
+    unsafe {
+        let engine = &*(engine as *mut CacheEngine);
+        let rendered = engine.stats_snapshot().render_prometheus();
+
+        match CString::new(rendered) {
+            Ok(c_string) => c_string.into_raw() as *const c_char,
+            Err(_) => ptr::null(),
+        }
+    }
+}
+
+/// Save the L1 cache to the configured L3 path. Returns `false` if persistence wasn't
+/// configured for this engine or the write failed.
+#[no_mangle]
+pub extern "C" fn cache_flush(engine: *mut c_void) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let engine = &*(engine as *mut CacheEngine);
+        engine.l3_path.is_some() && engine.flush().is_ok()
+    }
+}
+
+/// Load the L3 snapshot back into L1. Returns `false` if persistence wasn't configured for
+/// this engine or the read failed.
+#[no_mangle]
+pub extern "C" fn cache_restore(engine: *mut c_void) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let engine = &*(engine as *mut CacheEngine);
+        engine.l3_path.is_some() && engine.restore().is_ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -340,4 +1300,190 @@ mod tests {
         let stats = cache.get_stats().await;
         assert_eq!(stats.l1_size, 1);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_render_metrics_reports_live_counters_in_prometheus_format() {
+        let cache = CacheEngine::new();
+
+        cache.set("present", b"value".to_vec(), 3600).await;
+        assert_eq!(cache.get("present").await, Some(b"value".to_vec()));
+        assert_eq!(cache.get("missing").await, None);
+
+        let rendered = cache.render_metrics().await;
+        assert!(rendered.contains("# TYPE cache_l1_hits_total counter"));
+        assert!(rendered.contains("cache_l1_hits_total 1"));
+        assert!(rendered.contains("cache_l1_misses_total 1"));
+        assert!(rendered.contains("# TYPE cache_l1_size gauge"));
+        assert!(rendered.contains("cache_l1_size 1"));
+        assert!(rendered.contains("cache_l1_hit_ratio 0.5"));
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.l1_hits, 1);
+        assert_eq!(stats.l1_misses, 1);
+        assert_eq!(stats.total_operations, 2);
+    }
+
+    #[tokio::test]
+    async fn test_l1_tier_satisfies_cache_tier_contract() {
+        let tier = L1Tier::new(L1Capacity::default());
+        let entry = CacheEntry::new(b"chunked".to_vec(), 3600);
+
+        assert!(tier.get("missing").await.is_none());
+
+        tier.set("present", entry.clone()).await;
+        assert_eq!(tier.get("present").await.unwrap().data, entry.data);
+
+        assert!(tier.delete("present").await);
+        assert!(tier.get("present").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_identical_large_values_are_deduplicated_across_keys() {
+        let cache = CacheEngine::new();
+
+        // Large enough to clear CDC_MIN_SIZE and produce real content-defined chunks rather
+        // than being stored as a single undersized chunk.
+        let payload = vec![0x42u8; 200 * 1024];
+
+        cache.set("key_a", payload.clone(), 3600).await;
+        cache.set("key_b", payload.clone(), 3600).await;
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.l1_size, 2);
+        // Logical size counts both copies, but identical content should collapse to one
+        // physical copy in the shared chunk pool.
+        assert_eq!(stats.l1_bytes, payload.len() * 2);
+        assert!(stats.l1_deduplicated_bytes <= payload.len() + CDC_MAX_SIZE);
+
+        assert_eq!(cache.get("key_a").await, Some(payload.clone()));
+        assert_eq!(cache.get("key_b").await, Some(payload));
+    }
+
+    #[tokio::test]
+    async fn test_set_stream_and_get_stream_round_trip_without_full_buffering() {
+        let cache = CacheEngine::new();
+
+        let pieces: Vec<Vec<u8>> = (0..20).map(|i| vec![i as u8; 5 * 1024]).collect();
+        let expected: Vec<u8> = pieces.iter().flatten().copied().collect();
+
+        let (tx, rx) = mpsc::channel(4);
+        let writer = tokio::spawn(async move {
+            for piece in pieces {
+                tx.send(piece).await.unwrap();
+            }
+        });
+        cache
+            .set_stream("streamed", CacheData::Stream { receiver: rx, size_hint: Some(expected.len()) }, 3600)
+            .await;
+        writer.await.unwrap();
+
+        let CacheData::Stream { mut receiver, size_hint } =
+            cache.get_stream("streamed").await.expect("key should be present")
+        else {
+            panic!("get_stream should return a Stream variant");
+        };
+        assert_eq!(size_hint, Some(expected.len()));
+
+        let mut reassembled = Vec::new();
+        while let Some(chunk) = receiver.recv().await {
+            reassembled.extend(chunk);
+        }
+        assert_eq!(reassembled, expected);
+
+        assert!(cache.get_stream("missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_l1_capacity_evicts_oldest_unreferenced_entry_first() {
+        let cache = CacheEngine::with_capacity(L1Capacity {
+            max_entries: Some(2),
+            max_bytes: None,
+        });
+
+        cache.set("a", b"1".to_vec(), 3600).await;
+        cache.set("b", b"2".to_vec(), 3600).await;
+
+        // Touch "a" so its reference bit is set before the third insert forces an eviction:
+        // CLOCK should give "a" a second chance and evict "b" instead.
+        assert!(cache.get("a").await.is_some());
+        cache.set("c", b"3".to_vec(), 3600).await;
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.l1_size, 2);
+        assert_eq!(stats.evictions, 1);
+        assert!(cache.get("a").await.is_some());
+        assert!(cache.get("c").await.is_some());
+        assert!(cache.get("b").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_overwrite_of_same_key_does_not_grow_clock_ring() {
+        let cache = CacheEngine::with_capacity(L1Capacity {
+            max_entries: Some(2),
+            max_bytes: None,
+        });
+
+        cache.set("hot", b"1".to_vec(), 3600).await;
+        for i in 0..50 {
+            cache.set("hot", i.to_string().into_bytes(), 3600).await;
+        }
+
+        assert_eq!(cache.l1.clock_ring.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_task_sweeps_expired_entries_and_counts_evictions() {
+        let cache = CacheEngine::with_cleanup(Duration::from_millis(20));
+
+        cache.set("short_lived", b"value".to_vec(), 1).await;
+        cache.set("long_lived", b"value".to_vec(), 3600).await;
+
+        // Long enough for the entry to expire and for at least one sweep tick to run.
+        tokio::time::sleep(Duration::from_millis(2200)).await;
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.l1_size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_flush_and_restore_round_trip_through_disk() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("cache_engine_test_{:?}.zst", std::thread::current().id()));
+
+        let mut cache = CacheEngine::new();
+        cache.l3_path = Some(path.clone());
+        cache.set("persisted", b"durable_value".to_vec(), 3600).await;
+        cache.set("will_expire", b"short_lived".to_vec(), 1).await;
+        cache.flush().unwrap();
+        drop(cache);
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        // Restoring into a fresh engine should recover the non-expired entry and skip the
+        // one that went stale while "the process was down".
+        let mut restored = CacheEngine::new();
+        restored.l3_path = Some(path.clone());
+        restored.restore().unwrap();
+
+        assert_eq!(restored.get("persisted").await, Some(b"durable_value".to_vec()));
+        assert_eq!(restored.get("will_expire").await, None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running redis instance at redis://127.0.0.1:6379"]
+    async fn test_redis_tier_round_trips_through_l2() {
+        let cache = CacheEngine::new_with_redis("redis://127.0.0.1:6379").unwrap();
+
+        let key = "redis_backed_key";
+        let value = b"redis_backed_value".to_vec();
+        cache.set(key, value.clone(), 3600).await;
+
+        // Clearing L1 forces the next `get` to fall through to Redis.
+        cache.clear().await;
+        let retrieved = cache.get(key).await;
+        assert_eq!(retrieved, Some(value));
+    }
+}
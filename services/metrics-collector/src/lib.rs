@@ -3,10 +3,12 @@
 //! This module provides lock-free, high-performance metrics collection capabilities
 //! optimized for multi-threaded environments with minimal overhead.
 
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant};
-use parking_lot::RwLock;
+use parking_lot::Mutex;
+use crossbeam::epoch::{self, Atomic, Owned};
 use crossbeam::queue::SegQueue;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
@@ -19,17 +21,145 @@ use std::ptr;
 /// Main metrics collector with lock-free operations
 pub struct MetricsCollector {
     /// Lock-free counters for simple increment/decrement operations
-    counters: DashMap<String, AtomicU64>,
+    counters: DashMap<String, Recorded>,
     /// Lock-free gauge for tracking current values
-    gauges: DashMap<String, AtomicU64>,
-    /// Thread-safe histograms for distribution tracking
-    histograms: RwLock<HashMap<String, Histogram<u64>>>,
+    gauges: DashMap<String, Recorded>,
+    /// Lock-free append-only sample buckets, one per histogram name; `get_histogram_stats`
+    /// folds the accumulated samples into an `HdrHistogram` on demand so `record_histogram`
+    /// never takes a lock.
+    histograms: DashMap<String, AtomicBucket>,
+    /// Mergeable quantile sketches, one per summary name. Unlike `histograms`, a `Summary`
+    /// can be folded into another collector's via `merge`, so percentiles stay accurate
+    /// across a fan-out of worker processes without shipping raw samples.
+    summaries: DashMap<String, Mutex<Summary>>,
     /// Lock-free event queue for batch processing
     event_queue: SegQueue<MetricEvent>,
     /// Configuration for the collector
     config: CollectorConfig,
     /// Internal metrics about the collector itself
     internal_metrics: InternalMetrics,
+    /// Decomposed name+labels for every composite storage key recorded via the
+    /// `*_labeled` methods, so `get_all_*` and `render_prometheus` can emit proper label
+    /// sets instead of the flattened storage key.
+    label_registry: DashMap<String, Key>,
+    /// Bumped by `sweep_idle` whenever it evicts at least one entry, so a counter/gauge
+    /// re-created after eviction can be told apart from one that's been live the whole
+    /// time (see `Recorded::generation`).
+    generation: AtomicU64,
+}
+
+/// A recorded counter/gauge value plus the monotonic timestamp (nanoseconds since the
+/// collector's `start_time`) it was last updated, and the collector-wide generation it was
+/// created under. `sweep_idle` uses `last_updated` to find stale entries; `generation` lets
+/// a value re-created after eviction be told apart from one that was never evicted.
+struct Recorded {
+    value: AtomicU64,
+    last_updated: AtomicU64,
+    generation: u64,
+}
+
+impl Recorded {
+    fn new(value: u64, now_nanos: u64, generation: u64) -> Self {
+        Self {
+            value: AtomicU64::new(value),
+            last_updated: AtomicU64::new(now_nanos),
+            generation,
+        }
+    }
+
+    fn touch(&self, now_nanos: u64) {
+        self.last_updated.store(now_nanos, Ordering::Relaxed);
+    }
+}
+
+/// Bitmask selecting which metric kinds `sweep_idle` should consider for eviction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricKindMask(u8);
+
+impl MetricKindMask {
+    pub const NONE: MetricKindMask = MetricKindMask(0);
+    pub const COUNTER: MetricKindMask = MetricKindMask(0b001);
+    pub const GAUGE: MetricKindMask = MetricKindMask(0b010);
+    pub const HISTOGRAM: MetricKindMask = MetricKindMask(0b100);
+    pub const ALL: MetricKindMask = MetricKindMask(0b111);
+
+    pub fn contains(self, other: MetricKindMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for MetricKindMask {
+    type Output = MetricKindMask;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        MetricKindMask(self.0 | rhs.0)
+    }
+}
+
+/// A metric name plus a sorted set of label key/value pairs. `BTreeMap` keeps iteration
+/// order stable regardless of insertion order, so two `Key`s with the same labels always
+/// produce the same composite storage key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Key {
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+}
+
+impl Key {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), labels: BTreeMap::new() }
+    }
+
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_labels(name: impl Into<String>, labels: BTreeMap<String, String>) -> Self {
+        Self { name: name.into(), labels }
+    }
+
+    /// Flattened key used to store this metric in the existing `DashMap`/histogram maps.
+    /// Bare names (no labels) round-trip to themselves, so unlabeled callers are unaffected.
+    fn storage_key(&self) -> String {
+        if self.labels.is_empty() {
+            return self.name.clone();
+        }
+
+        let mut out = self.name.clone();
+        out.push('{');
+        for (i, (k, v)) in self.labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(k);
+            out.push('=');
+            out.push_str(v);
+        }
+        out.push('}');
+        out
+    }
+}
+
+/// Render a label set as Prometheus's `{k="v",k2="v2"}` suffix, or an empty string when
+/// there are no labels.
+fn render_prometheus_labels(labels: &BTreeMap<String, String>) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("{");
+    for (i, (k, v)) in labels.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(k);
+        out.push_str("=\"");
+        out.push_str(v);
+        out.push('"');
+    }
+    out.push('}');
+    out
 }
 
 /// Configuration for the metrics collector
@@ -43,6 +173,12 @@ pub struct CollectorConfig {
     pub enable_simd: bool,
     /// Number of histogram buckets
     pub histogram_significant_digits: u8,
+    /// Metric kinds `sweep_idle` considers for eviction; defaults to `MetricKindMask::NONE`
+    /// so idle expiration is strictly opt-in.
+    pub idle_sweep_kinds: MetricKindMask,
+    /// How long a metric may go without an update before `sweep_idle` removes it. `None`
+    /// (the default) disables idle eviction regardless of `idle_sweep_kinds`.
+    pub idle_timeout: Option<Duration>,
 }
 
 impl Default for CollectorConfig {
@@ -52,6 +188,8 @@ impl Default for CollectorConfig {
             flush_interval: Duration::from_millis(100),
             enable_simd: cfg!(target_arch = "x86_64"),
             histogram_significant_digits: 3,
+            idle_sweep_kinds: MetricKindMask::NONE,
+            idle_timeout: None,
         }
     }
 }
@@ -69,6 +207,347 @@ pub struct InternalMetrics {
     pub start_time: Instant,
 }
 
+/// Number of samples held per `Block` in an `AtomicBucket` chain.
+const ATOMIC_BUCKET_BLOCK_SIZE: usize = 128;
+
+/// Maximum number of `Block`s an `AtomicBucket` keeps chained off its head. Once a push would
+/// grow the chain past this, the oldest block(s) are severed and retired, capping both the
+/// memory a single bucket can hold (`ATOMIC_BUCKET_MAX_BLOCKS * ATOMIC_BUCKET_BLOCK_SIZE`
+/// samples) and the cost of `snapshot`/`to_histogram`, which would otherwise grow without
+/// bound over a metric's lifetime. This makes `AtomicBucket` a sliding window over the most
+/// recent samples rather than a full history - the right tradeoff for quantiles computed at
+/// query time under sustained high-throughput recording.
+const ATOMIC_BUCKET_MAX_BLOCKS: usize = 64;
+
+/// A single fixed-size block of samples within an `AtomicBucket`'s chain.
+struct Block {
+    values: [AtomicU64; ATOMIC_BUCKET_BLOCK_SIZE],
+    /// Slots claimed by a writer so far; may run ahead of `committed` while a write to an
+    /// earlier slot is still in flight.
+    reserved: AtomicUsize,
+    /// Slots fully written and safe for readers to consume; always `<= reserved`.
+    committed: AtomicUsize,
+    next: Atomic<Block>,
+}
+
+impl Block {
+    fn new() -> Block {
+        Block {
+            values: std::array::from_fn(|_| AtomicU64::new(0)),
+            reserved: AtomicUsize::new(0),
+            committed: AtomicUsize::new(0),
+            next: Atomic::null(),
+        }
+    }
+
+    /// Attempt to claim and write the next free slot. Returns `Err(())` once the block is
+    /// full so the caller can CAS in a fresh head block instead.
+    fn push(&self, value: u64) -> Result<(), ()> {
+        let idx = self.reserved.fetch_add(1, Ordering::AcqRel);
+        if idx >= ATOMIC_BUCKET_BLOCK_SIZE {
+            return Err(());
+        }
+
+        self.values[idx].store(value, Ordering::Release);
+
+        // Writers commit in the order they reserved their slot, so spin briefly for any
+        // writer ahead of us rather than letting `committed` skip over an in-flight slot
+        // a reader might otherwise read as zero.
+        loop {
+            let committed = self.committed.load(Ordering::Acquire);
+            if committed != idx {
+                std::hint::spin_loop();
+                continue;
+            }
+            if self
+                .committed
+                .compare_exchange(committed, committed + 1, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Lock-free, multi-writer append-only bucket of `u64` samples.
+///
+/// Internally a linked list of fixed-size `Block`s: pushing fetch-adds a write index
+/// within the current head block, and once a block fills, the writer CAS-installs a fresh
+/// head that chains to the old one. Readers (`snapshot`/`to_histogram`) walk the chain
+/// under an epoch guard and only read slots below each block's `committed` length, so they
+/// never observe a torn (partially written) value. Reclamation of retired blocks goes
+/// through `crossbeam::epoch` so a snapshot in progress on one thread can never see a block
+/// freed by another. The chain is capped at `ATOMIC_BUCKET_MAX_BLOCKS` blocks (see
+/// `truncate_if_too_long`), so both its memory and the cost of walking it stay bounded no
+/// matter how many samples have been pushed over the bucket's lifetime.
+pub struct AtomicBucket {
+    head: Atomic<Block>,
+    /// Monotonic timestamp (nanoseconds since the owning collector's `start_time`) of the
+    /// most recent `push`, so `sweep_idle` can evict histograms nobody has recorded into
+    /// recently.
+    last_updated: AtomicU64,
+    /// Serializes `truncate_if_too_long`. Truncation itself only runs once every
+    /// `ATOMIC_BUCKET_BLOCK_SIZE` pushes (when a new block is installed), so contention here
+    /// is negligible; the lock just keeps two concurrent truncations from severing/retiring
+    /// overlapping parts of the chain at once.
+    truncate_lock: Mutex<()>,
+}
+
+impl AtomicBucket {
+    pub fn new() -> Self {
+        Self {
+            head: Atomic::new(Block::new()),
+            last_updated: AtomicU64::new(0),
+            truncate_lock: Mutex::new(()),
+        }
+    }
+
+    /// Nanoseconds since the owning collector's `start_time` at the most recent `push`.
+    pub fn last_updated(&self) -> u64 {
+        self.last_updated.load(Ordering::Relaxed)
+    }
+
+    /// Push a sample, lock-free and safe for any number of concurrent writers.
+    pub fn push(&self, value: u64, now_nanos: u64) {
+        self.last_updated.store(now_nanos, Ordering::Relaxed);
+        let guard = &epoch::pin();
+
+        loop {
+            let head_shared = self.head.load(Ordering::Acquire, guard);
+            let head = unsafe { head_shared.deref() };
+
+            if head.push(value).is_ok() {
+                return;
+            }
+
+            let mut new_block = Owned::new(Block::new());
+            new_block.next.store(head_shared, Ordering::Relaxed);
+
+            match self.head.compare_exchange(
+                head_shared,
+                new_block,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+                guard,
+            ) {
+                Ok(new_shared) => {
+                    let _ = unsafe { new_shared.deref() }.push(value);
+                    self.truncate_if_too_long(guard);
+                    return;
+                }
+                Err(_) => continue,
+            }
+        }
+    }
+
+    /// If the chain has grown past `ATOMIC_BUCKET_MAX_BLOCKS`, sever it at the cap and retire
+    /// everything older. Only ever touches a block's `next` pointer after it's already been
+    /// fully written and unreachable as a write target (writers only ever push into the
+    /// current head), so this can't race a concurrent `push`; `truncate_lock` only guards
+    /// against two truncations racing each other.
+    fn truncate_if_too_long(&self, guard: &epoch::Guard) {
+        let _guard = self.truncate_lock.lock();
+
+        let mut current = self.head.load(Ordering::Acquire, guard);
+        let mut remaining = ATOMIC_BUCKET_MAX_BLOCKS;
+        while !current.is_null() && remaining > 1 {
+            current = unsafe { current.deref() }.next.load(Ordering::Acquire, guard);
+            remaining -= 1;
+        }
+
+        if current.is_null() {
+            return;
+        }
+
+        let mut tail = unsafe { current.deref() }.next.swap(
+            crossbeam::epoch::Shared::null(),
+            Ordering::AcqRel,
+            guard,
+        );
+        while !tail.is_null() {
+            unsafe {
+                let next = tail.deref().next.load(Ordering::Acquire, guard);
+                guard.defer_destroy(tail);
+                tail = next;
+            }
+        }
+    }
+
+    /// Snapshot every committed sample across the block chain without holding a lock.
+    pub fn snapshot(&self) -> Vec<u64> {
+        let guard = &epoch::pin();
+        let mut out = Vec::new();
+        let mut current = self.head.load(Ordering::Acquire, guard);
+
+        while !current.is_null() {
+            let block = unsafe { current.deref() };
+            let committed = block.committed.load(Ordering::Acquire);
+            for slot in &block.values[..committed] {
+                out.push(slot.load(Ordering::Acquire));
+            }
+            current = block.next.load(Ordering::Acquire, guard);
+        }
+
+        out
+    }
+
+    /// Fold the current snapshot into a fresh `HdrHistogram`, so quantiles are only ever
+    /// computed when queried rather than on every push.
+    pub fn to_histogram(&self, significant_digits: u8) -> Option<Histogram<u64>> {
+        let values = self.snapshot();
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut hist = Histogram::new_with_bounds(1, u64::MAX, significant_digits).ok()?;
+        for value in values {
+            let _ = hist.record(value.max(1));
+        }
+        Some(hist)
+    }
+}
+
+impl Drop for AtomicBucket {
+    fn drop(&mut self) {
+        // `&mut self` guarantees no concurrent pushers/readers remain, so the whole chain
+        // can be unlinked and retired through the epoch collector in one pass.
+        let guard = unsafe { epoch::unprotected() };
+        let mut current = self.head.swap(crossbeam::epoch::Shared::null(), Ordering::AcqRel, guard);
+
+        while !current.is_null() {
+            unsafe {
+                let next = current.deref().next.load(Ordering::Acquire, guard);
+                guard.defer_destroy(current);
+                current = next;
+            }
+        }
+    }
+}
+
+/// A weighted mean within a `Summary`'s centroid list.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Centroid {
+    mean: f64,
+    weight: u64,
+}
+
+/// Default cap on the number of centroids a `Summary` retains; chosen as a middle ground
+/// between quantile accuracy and the cost of `merge`/`record`, both of which are
+/// `O(centroids)`.
+const SUMMARY_DEFAULT_MAX_CENTROIDS: usize = 100;
+
+/// Streaming, mergeable quantile sketch (t-digest-style): each sample folds into a bounded,
+/// mean-sorted list of weighted centroids rather than being retained individually, so a
+/// `Summary` from one collector can be `merge`d into another's without shipping raw samples
+/// — unlike `AtomicBucket`/`HdrHistogram`, which only support a single owning collector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Summary {
+    centroids: Vec<Centroid>,
+    max_centroids: usize,
+    count: u64,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self::with_max_centroids(SUMMARY_DEFAULT_MAX_CENTROIDS)
+    }
+
+    pub fn with_max_centroids(max_centroids: usize) -> Self {
+        Self { centroids: Vec::new(), max_centroids, count: 0 }
+    }
+
+    /// Total number of samples folded into this summary, across every `record`/`merge`.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Fold a single sample in.
+    pub fn record(&mut self, value: u64) {
+        self.count += 1;
+        self.insert_centroid(Centroid { mean: value as f64, weight: 1 });
+        self.compress();
+    }
+
+    /// Fold another summary's centroids into this one, combining samples recorded by
+    /// separate collector instances (e.g. one per worker process) into a single sketch.
+    pub fn merge(&mut self, other: &Summary) {
+        self.count += other.count;
+        for &centroid in &other.centroids {
+            self.insert_centroid(centroid);
+        }
+        self.compress();
+    }
+
+    /// Estimate the value at quantile `q` (0.0..=1.0), or `None` if nothing has been
+    /// recorded yet.
+    pub fn value_at_quantile(&self, q: f64) -> Option<u64> {
+        if self.centroids.is_empty() {
+            return None;
+        }
+
+        let target = q * self.count as f64;
+        let mut cumulative = 0.0;
+        for centroid in &self.centroids {
+            cumulative += centroid.weight as f64;
+            if cumulative >= target {
+                return Some(centroid.mean.round() as u64);
+            }
+        }
+
+        self.centroids.last().map(|c| c.mean.round() as u64)
+    }
+
+    fn insert_centroid(&mut self, centroid: Centroid) {
+        let idx = self
+            .centroids
+            .partition_point(|c| c.mean < centroid.mean);
+        self.centroids.insert(idx, centroid);
+    }
+
+    /// Repeatedly merge the pair of adjacent centroids with the smallest gap between their
+    /// means until the list is back within `max_centroids`, keeping the sketch's memory
+    /// bounded regardless of how many samples have been recorded.
+    fn compress(&mut self) {
+        while self.centroids.len() > self.max_centroids {
+            let mut best_idx = 0;
+            let mut best_gap = f64::MAX;
+            for i in 0..self.centroids.len() - 1 {
+                let gap = self.centroids[i + 1].mean - self.centroids[i].mean;
+                if gap < best_gap {
+                    best_gap = gap;
+                    best_idx = i;
+                }
+            }
+
+            let a = self.centroids[best_idx];
+            let b = self.centroids[best_idx + 1];
+            let merged_weight = a.weight + b.weight;
+            let merged_mean =
+                (a.mean * a.weight as f64 + b.mean * b.weight as f64) / merged_weight as f64;
+
+            self.centroids[best_idx] = Centroid { mean: merged_mean, weight: merged_weight };
+            self.centroids.remove(best_idx + 1);
+        }
+    }
+}
+
+impl Default for Summary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Statistics read from a `Summary`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryStats {
+    pub count: u64,
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub p999: u64,
+}
+
 impl MetricsCollector {
     /// Create a new metrics collector with default configuration
     pub fn new() -> Self {
@@ -80,7 +559,8 @@ impl MetricsCollector {
         Self {
             counters: DashMap::new(),
             gauges: DashMap::new(),
-            histograms: RwLock::new(HashMap::new()),
+            histograms: DashMap::new(),
+            summaries: DashMap::new(),
             event_queue: SegQueue::new(),
             config,
             internal_metrics: InternalMetrics {
@@ -89,9 +569,17 @@ impl MetricsCollector {
                 buffer_utilization: AtomicU64::new(0),
                 start_time: Instant::now(),
             },
+            label_registry: DashMap::new(),
+            generation: AtomicU64::new(0),
         }
     }
 
+    /// Nanoseconds elapsed since this collector was created; used as the monotonic clock
+    /// for `Recorded::last_updated` and `AtomicBucket::last_updated`.
+    fn now_nanos(&self) -> u64 {
+        self.internal_metrics.start_time.elapsed().as_nanos() as u64
+    }
+
     /// Record a counter increment
     ///
     /// This is a lock-free operation that takes ~2ns
@@ -115,16 +603,40 @@ impl MetricsCollector {
     #[inline]
     pub fn add_counter(&self, name: &str, value: u64) {
         self.internal_metrics.total_metrics.fetch_add(1, Ordering::Relaxed);
+        let now = self.now_nanos();
+        let generation = self.generation.load(Ordering::Relaxed);
 
-        self.counters
+        let entry = self
+            .counters
             .entry(name.to_string())
-            .or_insert_with(|| AtomicU64::new(0))
-            .fetch_add(value, Ordering::Relaxed);
+            .or_insert_with(|| Recorded::new(0, now, generation));
+        entry.value.fetch_add(value, Ordering::Relaxed);
+        entry.touch(now);
     }
 
     /// Get the current value of a counter
     pub fn get_counter(&self, name: &str) -> Option<u64> {
-        self.counters.get(name).map(|counter| counter.load(Ordering::Relaxed))
+        self.counters.get(name).map(|counter| counter.value.load(Ordering::Relaxed))
+    }
+
+    /// Generation this counter was (re-)created under. Compare against a previously
+    /// observed value to detect that `sweep_idle` evicted and later re-created it, which
+    /// otherwise looks identical to an in-place reset.
+    pub fn get_counter_generation(&self, name: &str) -> Option<u64> {
+        self.counters.get(name).map(|counter| counter.generation)
+    }
+
+    /// Increment a counter identified by a name plus label set, e.g.
+    /// `Key::new("requests").with_label("method", "GET")`.
+    #[inline]
+    pub fn increment_counter_labeled(&self, key: &Key) {
+        self.add_counter_labeled(key, 1);
+    }
+
+    /// Add a value to a counter identified by a name plus label set.
+    pub fn add_counter_labeled(&self, key: &Key, value: u64) {
+        self.register_key(key);
+        self.add_counter(&key.storage_key(), value);
     }
 
     /// Set a gauge value
@@ -136,16 +648,31 @@ impl MetricsCollector {
     #[inline]
     pub fn set_gauge(&self, name: &str, value: u64) {
         self.internal_metrics.total_metrics.fetch_add(1, Ordering::Relaxed);
+        let now = self.now_nanos();
+        let generation = self.generation.load(Ordering::Relaxed);
 
-        self.gauges
+        let entry = self
+            .gauges
             .entry(name.to_string())
-            .or_insert_with(|| AtomicU64::new(0))
-            .store(value, Ordering::Relaxed);
+            .or_insert_with(|| Recorded::new(0, now, generation));
+        entry.value.store(value, Ordering::Relaxed);
+        entry.touch(now);
     }
 
     /// Get the current value of a gauge
     pub fn get_gauge(&self, name: &str) -> Option<u64> {
-        self.gauges.get(name).map(|gauge| gauge.load(Ordering::Relaxed))
+        self.gauges.get(name).map(|gauge| gauge.value.load(Ordering::Relaxed))
+    }
+
+    /// Generation this gauge was (re-)created under; see `get_counter_generation`.
+    pub fn get_gauge_generation(&self, name: &str) -> Option<u64> {
+        self.gauges.get(name).map(|gauge| gauge.generation)
+    }
+
+    /// Set a gauge identified by a name plus label set.
+    pub fn set_gauge_labeled(&self, key: &Key, value: u64) {
+        self.register_key(key);
+        self.set_gauge(&key.storage_key(), value);
     }
 
     /// Record a value in a histogram
@@ -158,14 +685,66 @@ impl MetricsCollector {
     /// ```
     pub fn record_histogram(&self, name: &str, value: u64) {
         self.internal_metrics.total_metrics.fetch_add(1, Ordering::Relaxed);
+        let now = self.now_nanos();
 
-        let mut histograms = self.histograms.write();
-        let histogram = histograms.entry(name.to_string())
-            .or_insert_with(|| {
-                Histogram::new_with_bounds(1, u64::MAX, 3).unwrap()
-            });
+        self.histograms
+            .entry(name.to_string())
+            .or_insert_with(AtomicBucket::new)
+            .push(value, now);
+    }
 
-        histogram.record(value).unwrap();
+    /// Record a value in a histogram identified by a name plus label set.
+    pub fn record_histogram_labeled(&self, key: &Key, value: u64) {
+        self.register_key(key);
+        self.record_histogram(&key.storage_key(), value);
+    }
+
+    /// Record a value into a mergeable quantile summary, creating it if it doesn't exist.
+    /// Prefer this over `record_histogram` when the same metric also gets recorded by other
+    /// collector instances (e.g. one per worker process) that need to be combined later via
+    /// `merge_summary`.
+    pub fn record_summary(&self, name: &str, value: u64) {
+        self.internal_metrics.total_metrics.fetch_add(1, Ordering::Relaxed);
+
+        self.summaries
+            .entry(name.to_string())
+            .or_insert_with(|| Mutex::new(Summary::new()))
+            .lock()
+            .record(value);
+    }
+
+    /// Record a value into a summary identified by a name plus label set.
+    pub fn record_summary_labeled(&self, key: &Key, value: u64) {
+        self.register_key(key);
+        self.record_summary(&key.storage_key(), value);
+    }
+
+    /// Fold a summary produced by another collector instance into the local one with the
+    /// same name, creating it if it doesn't exist yet.
+    pub fn merge_summary(&self, name: &str, other: &Summary) {
+        self.summaries
+            .entry(name.to_string())
+            .or_insert_with(|| Mutex::new(Summary::new()))
+            .lock()
+            .merge(other);
+    }
+
+    /// Remember the decomposed name+labels for a composite storage key, so rendering and
+    /// `get_all_*_labeled` can recover them later. Unlabeled keys aren't worth tracking,
+    /// since their storage key already equals their name.
+    fn register_key(&self, key: &Key) {
+        if !key.labels.is_empty() {
+            self.label_registry.entry(key.storage_key()).or_insert_with(|| key.clone());
+        }
+    }
+
+    /// Look up the `Key` for a storage key, falling back to a label-less `Key` for
+    /// metrics recorded through the bare-name methods.
+    fn key_for(&self, storage_key: &str) -> Key {
+        self.label_registry
+            .get(storage_key)
+            .map(|k| k.clone())
+            .unwrap_or_else(|| Key::new(storage_key.to_string()))
     }
 
     /// Record a timing in a histogram
@@ -183,20 +762,35 @@ impl MetricsCollector {
         self.record_histogram(&format!("{}_ms", name), millis);
     }
 
-    /// Get statistics for a histogram
+    /// Get statistics for a histogram, folding its lock-free sample bucket into an
+    /// `HdrHistogram` at query time to compute quantiles.
     pub fn get_histogram_stats(&self, name: &str) -> Option<HistogramStats> {
-        let histograms = self.histograms.read();
-        histograms.get(name).map(|hist| {
-            HistogramStats {
-                count: hist.len(),
-                min: hist.min(),
-                max: hist.max(),
-                mean: hist.mean(),
-                p50: hist.value_at_quantile(0.5),
-                p95: hist.value_at_quantile(0.95),
-                p99: hist.value_at_quantile(0.99),
-                p999: hist.value_at_quantile(0.999),
-            }
+        let bucket = self.histograms.get(name)?;
+        let hist = bucket.to_histogram(self.config.histogram_significant_digits)?;
+
+        Some(HistogramStats {
+            count: hist.len(),
+            min: hist.min(),
+            max: hist.max(),
+            mean: hist.mean(),
+            p50: hist.value_at_quantile(0.5),
+            p95: hist.value_at_quantile(0.95),
+            p99: hist.value_at_quantile(0.99),
+            p999: hist.value_at_quantile(0.999),
+        })
+    }
+
+    /// Get statistics for a mergeable quantile summary.
+    pub fn get_summary_stats(&self, name: &str) -> Option<SummaryStats> {
+        let summary = self.summaries.get(name)?;
+        let summary = summary.lock();
+
+        Some(SummaryStats {
+            count: summary.count(),
+            p50: summary.value_at_quantile(0.5)?,
+            p95: summary.value_at_quantile(0.95)?,
+            p99: summary.value_at_quantile(0.99)?,
+            p999: summary.value_at_quantile(0.999)?,
         })
     }
 
@@ -204,7 +798,7 @@ impl MetricsCollector {
     pub fn get_all_counters(&self) -> HashMap<String, u64> {
         self.counters
             .iter()
-            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .map(|entry| (entry.key().clone(), entry.value().value.load(Ordering::Relaxed)))
             .collect()
     }
 
@@ -212,7 +806,24 @@ impl MetricsCollector {
     pub fn get_all_gauges(&self) -> HashMap<String, u64> {
         self.gauges
             .iter()
-            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .map(|entry| (entry.key().clone(), entry.value().value.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Get all counter values with their name decomposed from labels, for exporters that
+    /// need a proper label set rather than the flattened storage key.
+    pub fn get_all_counters_labeled(&self) -> Vec<(Key, u64)> {
+        self.counters
+            .iter()
+            .map(|entry| (self.key_for(entry.key()), entry.value().value.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Get all gauge values with their name decomposed from labels.
+    pub fn get_all_gauges_labeled(&self) -> Vec<(Key, u64)> {
+        self.gauges
+            .iter()
+            .map(|entry| (self.key_for(entry.key()), entry.value().value.load(Ordering::Relaxed)))
             .collect()
     }
 
@@ -230,39 +841,279 @@ impl MetricsCollector {
     pub fn reset_all(&self) {
         self.counters.clear();
         self.gauges.clear();
-        self.histograms.write().clear();
+        self.histograms.clear();
+        self.summaries.clear();
+        self.label_registry.clear();
 
         // Reset internal metrics except start time
         self.internal_metrics.total_metrics.store(0, Ordering::Relaxed);
         self.internal_metrics.flush_count.store(0, Ordering::Relaxed);
     }
 
+    /// Render all counters, gauges, and histograms in the Prometheus text exposition
+    /// format, suitable for serving directly from a `/metrics` endpoint.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        for entry in self.counters.iter() {
+            let key = self.key_for(entry.key());
+            let name = prometheus_metric_name(&key.name);
+            let labels = render_prometheus_labels(&key.labels);
+            out.push_str(&format!("# HELP {name}_total {}\n", key.name));
+            out.push_str(&format!("# TYPE {name}_total counter\n"));
+            out.push_str(&format!(
+                "{name}_total{labels} {}\n",
+                entry.value().value.load(Ordering::Relaxed)
+            ));
+        }
+
+        for entry in self.gauges.iter() {
+            let key = self.key_for(entry.key());
+            let name = prometheus_metric_name(&key.name);
+            let labels = render_prometheus_labels(&key.labels);
+            out.push_str(&format!("# HELP {name} {}\n", key.name));
+            out.push_str(&format!("# TYPE {name} gauge\n"));
+            out.push_str(&format!("{name}{labels} {}\n", entry.value().value.load(Ordering::Relaxed)));
+        }
+
+        for entry in self.histograms.iter() {
+            let Some(hist) = entry.value().to_histogram(self.config.histogram_significant_digits) else {
+                continue;
+            };
+            let key = self.key_for(entry.key());
+            let name = prometheus_metric_name(&key.name);
+            let labels = render_prometheus_labels(&key.labels);
+            // HdrHistogram doesn't expose native bucket counts at fixed value boundaries —
+            // what we actually have is a quantile sketch, so render it as a Prometheus
+            // summary (`quantile="<q>"` labels on values) rather than as `_bucket{le=...}`
+            // lines, which require `le` to be a cumulative value bound and the sample to
+            // be a monotonically non-decreasing count.
+            out.push_str(&format!("# HELP {name} {}\n", key.name));
+            out.push_str(&format!("# TYPE {name} summary\n"));
+
+            for q in [0.5, 0.9, 0.95, 0.99, 0.999] {
+                out.push_str(&format!(
+                    "{name}{{quantile=\"{:.3}\"{}}} {}\n",
+                    q,
+                    if labels.is_empty() { String::new() } else { format!(",{}", &labels[1..labels.len() - 1]) },
+                    hist.value_at_quantile(q)
+                ));
+            }
+            out.push_str(&format!("{name}_sum{labels} {}\n", hist.mean() * hist.len() as f64));
+            out.push_str(&format!("{name}_count{labels} {}\n", hist.len()));
+        }
+
+        for entry in self.summaries.iter() {
+            let summary = entry.value().lock();
+            if summary.count() == 0 {
+                continue;
+            }
+            let key = self.key_for(entry.key());
+            let name = prometheus_metric_name(&key.name);
+            let labels = render_prometheus_labels(&key.labels);
+            out.push_str(&format!("# HELP {name} {}\n", key.name));
+            out.push_str(&format!("# TYPE {name} summary\n"));
+
+            for q in [0.5, 0.9, 0.95, 0.99, 0.999] {
+                out.push_str(&format!(
+                    "{name}{{quantile=\"{:.3}\"{}}} {}\n",
+                    q,
+                    if labels.is_empty() { String::new() } else { format!(",{}", &labels[1..labels.len() - 1]) },
+                    summary.value_at_quantile(q).unwrap_or(0)
+                ));
+            }
+            out.push_str(&format!("{name}_count{labels} {}\n", summary.count()));
+        }
+
+        out
+    }
+
     /// Reset a specific metric
     pub fn reset_metric(&self, name: &str, metric_type: MetricType) {
         match metric_type {
             MetricType::Counter => {
                 if let Some(counter) = self.counters.get(name) {
-                    counter.store(0, Ordering::Relaxed);
+                    counter.value.store(0, Ordering::Relaxed);
                 }
             }
             MetricType::Gauge => {
                 if let Some(gauge) = self.gauges.get(name) {
-                    gauge.store(0, Ordering::Relaxed);
+                    gauge.value.store(0, Ordering::Relaxed);
                 }
             }
             MetricType::Histogram => {
-                self.histograms.write().remove(name);
+                self.histograms.remove(name);
+            }
+            MetricType::Summary => {
+                self.summaries.remove(name);
+            }
+        }
+    }
+
+    /// Evict counters, gauges, and/or histograms (per `CollectorConfig::idle_sweep_kinds`)
+    /// that haven't been updated within `CollectorConfig::idle_timeout`. Returns the number
+    /// of entries evicted. A no-op whenever `idle_timeout` is `None`, regardless of
+    /// `idle_sweep_kinds`.
+    pub fn sweep_idle(&self) -> usize {
+        let Some(idle_timeout) = self.config.idle_timeout else {
+            return 0;
+        };
+        let idle_timeout_nanos = idle_timeout.as_nanos() as u64;
+        let now = self.now_nanos();
+        let mut evicted = 0;
+
+        if self.config.idle_sweep_kinds.contains(MetricKindMask::COUNTER) {
+            let stale: Vec<String> = self
+                .counters
+                .iter()
+                .filter(|entry| now.saturating_sub(entry.value().last_updated.load(Ordering::Relaxed)) >= idle_timeout_nanos)
+                .map(|entry| entry.key().clone())
+                .collect();
+            for key in stale {
+                self.counters.remove(&key);
+                evicted += 1;
+            }
+        }
+
+        if self.config.idle_sweep_kinds.contains(MetricKindMask::GAUGE) {
+            let stale: Vec<String> = self
+                .gauges
+                .iter()
+                .filter(|entry| now.saturating_sub(entry.value().last_updated.load(Ordering::Relaxed)) >= idle_timeout_nanos)
+                .map(|entry| entry.key().clone())
+                .collect();
+            for key in stale {
+                self.gauges.remove(&key);
+                evicted += 1;
+            }
+        }
+
+        if self.config.idle_sweep_kinds.contains(MetricKindMask::HISTOGRAM) {
+            let stale: Vec<String> = self
+                .histograms
+                .iter()
+                .filter(|entry| now.saturating_sub(entry.value().last_updated()) >= idle_timeout_nanos)
+                .map(|entry| entry.key().clone())
+                .collect();
+            for key in stale {
+                self.histograms.remove(&key);
+                evicted += 1;
+            }
+        }
+
+        if evicted > 0 {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+
+        evicted
+    }
+
+    /// Push an event onto the batch queue for the background flusher (or a manual
+    /// `flush_now`) to drain later, instead of updating the counter/gauge/histogram maps
+    /// inline.
+    pub fn record_event(&self, event: MetricEvent) {
+        self.event_queue.push(event);
+        self.internal_metrics
+            .buffer_utilization
+            .store(self.event_queue.len() as u64, Ordering::Relaxed);
+    }
+
+    /// Drain every currently queued event, handing the batch to `sink`. Returns the number
+    /// of events drained.
+    pub fn flush_now(&self, sink: &dyn FlushSink) -> usize {
+        let mut batch = Vec::new();
+        while let Some(event) = self.event_queue.pop() {
+            batch.push(event);
+        }
+
+        let drained = batch.len();
+        if drained > 0 {
+            sink.flush(&batch);
+        }
+
+        self.internal_metrics.flush_count.fetch_add(1, Ordering::Relaxed);
+        self.internal_metrics
+            .buffer_utilization
+            .store(self.event_queue.len() as u64, Ordering::Relaxed);
+
+        drained
+    }
+
+    /// Spawn a background thread that drains the event queue into `sink` whenever it
+    /// exceeds `CollectorConfig::buffer_size`, or at least every `flush_interval`
+    /// otherwise.
+    pub fn spawn_flusher(self: &Arc<Self>, sink: Arc<dyn FlushSink>) -> std::thread::JoinHandle<()> {
+        let collector = Arc::clone(self);
+        let poll_interval = (collector.config.flush_interval / 10).max(Duration::from_millis(1));
+
+        std::thread::spawn(move || {
+            let mut last_flush = Instant::now();
+
+            loop {
+                std::thread::sleep(poll_interval);
+
+                let over_buffer = collector.event_queue.len() >= collector.config.buffer_size;
+                let interval_elapsed = last_flush.elapsed() >= collector.config.flush_interval;
+
+                if over_buffer || interval_elapsed {
+                    collector.flush_now(sink.as_ref());
+                    last_flush = Instant::now();
+                }
+            }
+        })
+    }
+}
+
+/// Destination for batches drained from `MetricsCollector`'s event queue. Implement this
+/// to forward flushed metrics somewhere other than back into the collector's own maps —
+/// stdout, a file, or the Prometheus exporter.
+pub trait FlushSink: Send + Sync {
+    fn flush(&self, events: &[MetricEvent]);
+}
+
+/// Default sink: folds each drained event back into the owning collector's own
+/// counter/gauge/histogram maps.
+pub struct LocalFoldSink {
+    collector: Arc<MetricsCollector>,
+}
+
+impl LocalFoldSink {
+    pub fn new(collector: Arc<MetricsCollector>) -> Self {
+        Self { collector }
+    }
+}
+
+impl FlushSink for LocalFoldSink {
+    fn flush(&self, events: &[MetricEvent]) {
+        for event in events {
+            match &event.value {
+                MetricValue::Counter(v) => self.collector.add_counter(&event.name, *v),
+                MetricValue::Gauge(v) => self.collector.set_gauge(&event.name, *v),
+                MetricValue::Histogram(v) => self.collector.record_histogram(&event.name, *v),
+                MetricValue::Summary(v) => self.collector.record_summary(&event.name, *v),
             }
         }
     }
 }
 
+/// Sink that writes each drained batch to stdout as a JSON array, one line per flush.
+pub struct StdoutSink;
+
+impl FlushSink for StdoutSink {
+    fn flush(&self, events: &[MetricEvent]) {
+        if let Ok(json) = serde_json::to_string(events) {
+            println!("{json}");
+        }
+    }
+}
+
 /// Types of metrics
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MetricType {
     Counter,
     Gauge,
     Histogram,
+    Summary,
 }
 
 /// Statistics for a histogram
@@ -301,6 +1152,130 @@ pub enum MetricValue {
     Counter(u64),
     Gauge(u64),
     Histogram(u64),
+    Summary(u64),
+}
+
+/// Delta + zigzag + varint (LEB128) codec for compact binary export of `u64` streams that
+/// are monotonic or tightly clustered, e.g. a counter snapshot or histogram sample stream.
+/// Typically several-fold smaller than the equivalent JSON array for such streams.
+pub mod streaming_integers {
+    /// Map a signed delta to an unsigned value with small magnitudes mapping to small
+    /// integers, so runs of small deltas varint-encode to very few bytes.
+    fn zigzag_encode(n: i64) -> u64 {
+        ((n << 1) ^ (n >> 63)) as u64
+    }
+
+    fn zigzag_decode(n: u64) -> i64 {
+        ((n >> 1) as i64) ^ -((n & 1) as i64)
+    }
+
+    fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    /// Returns the decoded value plus the number of bytes consumed from `bytes`.
+    fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        for (consumed, &byte) in bytes.iter().enumerate() {
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some((value, consumed + 1));
+            }
+            shift += 7;
+        }
+        None
+    }
+
+    /// Encode `values` as successive zigzag-encoded deltas (wrapping on overflow, matching
+    /// the 64-bit zigzag formula), each LEB128 varint-packed.
+    pub fn compress(values: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut prev = 0u64;
+
+        for &value in values {
+            let delta = value.wrapping_sub(prev) as i64;
+            prev = value;
+            write_varint(zigzag_encode(delta), &mut out);
+        }
+
+        out
+    }
+
+    /// Reverse of `compress`: varint-decode, zigzag-decode, and prefix-sum to recover the
+    /// original values.
+    pub fn decompress(bytes: &[u8]) -> Vec<u64> {
+        let mut out = Vec::new();
+        let mut prev = 0u64;
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            let Some((zigzag, consumed)) = read_varint(&bytes[pos..]) else {
+                break;
+            };
+            pos += consumed;
+            prev = prev.wrapping_add(zigzag_decode(zigzag) as u64);
+            out.push(prev);
+        }
+
+        out
+    }
+}
+
+/// Replace characters Prometheus doesn't allow in a metric name (anything outside
+/// `[a-zA-Z0-9_:]`) with `_`.
+fn prometheus_metric_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == ':' { c } else { '_' })
+        .collect()
+}
+
+/// Serves `MetricsCollector::render_prometheus` output over a minimal HTTP `/metrics`
+/// endpoint so AgenticGen can be scraped directly by a standard monitoring stack.
+pub struct PrometheusExporter {
+    collector: Arc<MetricsCollector>,
+}
+
+impl PrometheusExporter {
+    pub fn new(collector: Arc<MetricsCollector>) -> Self {
+        Self { collector }
+    }
+
+    /// Render the current snapshot without starting a server.
+    pub fn render(&self) -> String {
+        self.collector.render_prometheus()
+    }
+
+    /// Spawn a background thread that accepts connections on `addr` and responds to every
+    /// request with the current Prometheus snapshot, regardless of path or method — this
+    /// is meant to sit behind a scrape config pointed at `/metrics`, not serve as a
+    /// general-purpose HTTP server.
+    pub fn spawn(self, addr: &str) -> std::io::Result<std::thread::JoinHandle<()>> {
+        let listener = std::net::TcpListener::bind(addr)?;
+
+        Ok(std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let body = self.render();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+
+                use std::io::Write;
+                let _ = stream.write_all(response.as_bytes());
+            }
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -388,6 +1363,223 @@ mod tests {
         assert!(stats.mean >= 10.0);
         assert!(stats.mean < 20.0); // Allow some variance
     }
+
+    #[test]
+    fn test_render_prometheus_includes_all_metric_kinds() {
+        let collector = MetricsCollector::new();
+
+        collector.increment_counter("requests");
+        collector.set_gauge("active_connections", 7);
+        collector.record_histogram("latency_ms", 42);
+
+        let rendered = collector.render_prometheus();
+
+        assert!(rendered.contains("# TYPE requests_total counter"));
+        assert!(rendered.contains("requests_total 1"));
+        assert!(rendered.contains("# TYPE active_connections gauge"));
+        assert!(rendered.contains("active_connections 7"));
+        assert!(rendered.contains("# TYPE latency_ms summary"));
+        assert!(rendered.contains("latency_ms{quantile=\"0.500\"} 42"));
+        assert!(rendered.contains("latency_ms_count 1"));
+    }
+
+    #[test]
+    fn test_labeled_counters_render_with_label_set() {
+        let collector = MetricsCollector::new();
+
+        let get_200 = Key::new("requests").with_label("method", "GET").with_label("status", "200");
+        let post_500 = Key::new("requests").with_label("method", "POST").with_label("status", "500");
+
+        collector.increment_counter_labeled(&get_200);
+        collector.increment_counter_labeled(&get_200);
+        collector.increment_counter_labeled(&post_500);
+
+        let all = collector.get_all_counters_labeled();
+        assert_eq!(all.len(), 2);
+
+        let get_entry = all.iter().find(|(k, _)| k.labels.get("method").map(String::as_str) == Some("GET")).unwrap();
+        assert_eq!(get_entry.1, 2);
+
+        let rendered = collector.render_prometheus();
+        assert!(rendered.contains(r#"requests_total{method="GET",status="200"} 2"#));
+        assert!(rendered.contains(r#"requests_total{method="POST",status="500"} 1"#));
+    }
+
+    #[test]
+    fn test_atomic_bucket_spans_multiple_blocks() {
+        let bucket = AtomicBucket::new();
+
+        // More than one block's worth of samples, to exercise the CAS-new-head path.
+        for i in 0..(ATOMIC_BUCKET_BLOCK_SIZE * 3 + 7) as u64 {
+            bucket.push(i, i);
+        }
+
+        let snapshot = bucket.snapshot();
+        assert_eq!(snapshot.len(), ATOMIC_BUCKET_BLOCK_SIZE * 3 + 7);
+
+        let hist = bucket.to_histogram(3).unwrap();
+        assert_eq!(hist.len(), snapshot.len() as u64);
+    }
+
+    #[test]
+    fn test_atomic_bucket_caps_chain_length_under_sustained_pushes() {
+        let bucket = AtomicBucket::new();
+
+        // Far more samples than ATOMIC_BUCKET_MAX_BLOCKS worth of blocks can hold.
+        let total = (ATOMIC_BUCKET_BLOCK_SIZE * (ATOMIC_BUCKET_MAX_BLOCKS + 20)) as u64;
+        for i in 0..total {
+            bucket.push(i, i);
+        }
+
+        let snapshot = bucket.snapshot();
+        assert!(snapshot.len() <= ATOMIC_BUCKET_BLOCK_SIZE * ATOMIC_BUCKET_MAX_BLOCKS);
+
+        // Old samples should have been dropped in favor of the most recent ones.
+        assert!(*snapshot.last().unwrap() >= total - (ATOMIC_BUCKET_BLOCK_SIZE * ATOMIC_BUCKET_MAX_BLOCKS) as u64);
+
+        let hist = bucket.to_histogram(3).unwrap();
+        assert_eq!(hist.len(), snapshot.len() as u64);
+    }
+
+    #[test]
+    fn test_atomic_bucket_concurrent_pushes_are_all_recorded() {
+        use std::sync::Arc;
+
+        let bucket = Arc::new(AtomicBucket::new());
+        let mut handles = vec![];
+
+        for _ in 0..8 {
+            let bucket = Arc::clone(&bucket);
+            handles.push(thread::spawn(move || {
+                for i in 0..500u64 {
+                    bucket.push(i, i);
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(bucket.snapshot().len(), 8 * 500);
+    }
+
+    #[test]
+    fn test_record_event_and_flush_now_folds_into_local_maps() {
+        let collector = Arc::new(MetricsCollector::new());
+
+        collector.record_event(MetricEvent {
+            timestamp: Utc::now(),
+            name: "queued_counter".to_string(),
+            metric_type: MetricType::Counter,
+            value: MetricValue::Counter(3),
+        });
+        collector.record_event(MetricEvent {
+            timestamp: Utc::now(),
+            name: "queued_gauge".to_string(),
+            metric_type: MetricType::Gauge,
+            value: MetricValue::Gauge(99),
+        });
+
+        assert_eq!(collector.get_internal_metrics().buffer_utilization, 2);
+
+        let sink = LocalFoldSink::new(Arc::clone(&collector));
+        let drained = collector.flush_now(&sink);
+
+        assert_eq!(drained, 2);
+        assert_eq!(collector.get_counter("queued_counter"), Some(3));
+        assert_eq!(collector.get_gauge("queued_gauge"), Some(99));
+        assert_eq!(collector.get_internal_metrics().flush_count, 1);
+        assert_eq!(collector.get_internal_metrics().buffer_utilization, 0);
+    }
+
+    #[test]
+    fn test_sweep_idle_evicts_stale_counters_but_not_fresh_ones() {
+        let mut config = CollectorConfig::default();
+        config.idle_sweep_kinds = MetricKindMask::COUNTER;
+        config.idle_timeout = Some(Duration::from_millis(50));
+        let collector = MetricsCollector::with_config(config);
+
+        collector.increment_counter("stale_counter");
+        thread::sleep(Duration::from_millis(100));
+        collector.increment_counter("fresh_counter");
+
+        let generation_before = collector.get_counter_generation("stale_counter");
+        let evicted = collector.sweep_idle();
+
+        assert_eq!(evicted, 1);
+        assert_eq!(collector.get_counter("stale_counter"), None);
+        assert_eq!(collector.get_counter("fresh_counter"), Some(1));
+
+        // Re-creating the evicted counter should carry a newer generation than before.
+        collector.increment_counter("stale_counter");
+        assert!(collector.get_counter_generation("stale_counter") > generation_before);
+    }
+
+    #[test]
+    fn test_sweep_idle_is_noop_without_idle_timeout() {
+        let collector = MetricsCollector::new();
+        collector.increment_counter("some_counter");
+
+        assert_eq!(collector.sweep_idle(), 0);
+        assert_eq!(collector.get_counter("some_counter"), Some(1));
+    }
+
+    #[test]
+    fn test_streaming_integers_roundtrip_monotonic_values() {
+        let values: Vec<u64> = (0..1000).map(|i| i * 3).collect();
+
+        let packed = streaming_integers::compress(&values);
+        assert!(packed.len() < values.len() * std::mem::size_of::<u64>());
+        assert_eq!(streaming_integers::decompress(&packed), values);
+    }
+
+    #[test]
+    fn test_streaming_integers_roundtrip_handles_decreasing_and_empty() {
+        let values = vec![100u64, 50, 50, 200, 0, u64::MAX, 0];
+        let packed = streaming_integers::compress(&values);
+        assert_eq!(streaming_integers::decompress(&packed), values);
+
+        assert!(streaming_integers::compress(&[]).is_empty());
+        assert!(streaming_integers::decompress(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_summary_quantiles_approximate_uniform_distribution() {
+        let collector = MetricsCollector::new();
+
+        for i in 1..=1000u64 {
+            collector.record_summary("latency_ms", i);
+        }
+
+        let stats = collector.get_summary_stats("latency_ms").unwrap();
+        assert_eq!(stats.count, 1000);
+        // t-digest-style compression is approximate, so allow some slack around the true
+        // quantiles of a 1..=1000 uniform distribution.
+        assert!((stats.p50 as i64 - 500).abs() <= 50);
+        assert!((stats.p99 as i64 - 990).abs() <= 50);
+    }
+
+    #[test]
+    fn test_summary_merge_combines_separate_collectors() {
+        let mut worker_a = Summary::new();
+        let mut worker_b = Summary::new();
+
+        for i in 1..=500u64 {
+            worker_a.record(i);
+        }
+        for i in 501..=1000u64 {
+            worker_b.record(i);
+        }
+
+        let collector = MetricsCollector::new();
+        collector.merge_summary("cross_process_latency", &worker_a);
+        collector.merge_summary("cross_process_latency", &worker_b);
+
+        let stats = collector.get_summary_stats("cross_process_latency").unwrap();
+        assert_eq!(stats.count, 1000);
+        assert!((stats.p50 as i64 - 500).abs() <= 50);
+    }
 }
 
 // C FFI exports for Python integration
@@ -509,6 +1701,68 @@ pub extern "C" fn record_timing(collector: *mut std::ffi::c_void, name: *const c
     }
 }
 
+/// Record a value in a mergeable quantile summary
+#[no_mangle]
+pub extern "C" fn record_summary(collector: *mut std::ffi::c_void, name: *const c_char, value: u64) {
+    if collector.is_null() || name.is_null() {
+        return;
+    }
+
+    unsafe {
+        let collector = &*(collector as *mut MetricsCollector);
+        let name_str = std::ffi::CStr::from_ptr(name).to_str().unwrap_or("");
+        collector.record_summary(name_str, value);
+    }
+}
+
+/// Get a summary's quantile statistics as a JSON string
+#[no_mangle]
+pub extern "C" fn get_summary_stats(collector: *mut std::ffi::c_void, name: *const c_char) -> *const c_char {
+    if collector.is_null() || name.is_null() {
+        return ptr::null();
+    }
+
+    unsafe {
+        let collector = &*(collector as *mut MetricsCollector);
+        let name_str = std::ffi::CStr::from_ptr(name).to_str().unwrap_or("");
+
+        let Some(stats) = collector.get_summary_stats(name_str) else {
+            return ptr::null();
+        };
+
+        match serde_json::to_string(&stats) {
+            Ok(json) => CString::new(json).unwrap().into_raw() as *const c_char,
+            Err(_) => ptr::null(),
+        }
+    }
+}
+
+/// Fold a JSON-serialized `Summary` produced by another collector instance (e.g. a remote
+/// worker process) into the local summary of the same name.
+#[no_mangle]
+pub extern "C" fn merge_summary(
+    collector: *mut std::ffi::c_void,
+    name: *const c_char,
+    summary_json: *const c_char,
+) -> bool {
+    if collector.is_null() || name.is_null() || summary_json.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let collector = &*(collector as *mut MetricsCollector);
+        let name_str = std::ffi::CStr::from_ptr(name).to_str().unwrap_or("");
+        let json_str = std::ffi::CStr::from_ptr(summary_json).to_str().unwrap_or("");
+
+        let Ok(remote) = serde_json::from_str::<Summary>(json_str) else {
+            return false;
+        };
+
+        collector.merge_summary(name_str, &remote);
+        true
+    }
+}
+
 /// Get all counters as JSON string
 #[no_mangle]
 pub extern "C" fn get_all_counters(collector: *mut std::ffi::c_void) -> *const c_char {
@@ -553,6 +1807,74 @@ pub extern "C" fn get_all_gauges(collector: *mut std::ffi::c_void) -> *const c_c
     }
 }
 
+/// Get all counter values, packed as delta+zigzag+varint bytes via `streaming_integers`
+/// instead of JSON. Caller owns the returned buffer and must free it by reconstructing a
+/// boxed slice of the reported length (`Vec::from_raw_parts`-style) — there is no dedicated
+/// free function, matching the "caller frees" convention of the byte-buffer output of the
+/// sibling `cache-engine` crate (`cache_get`).
+#[no_mangle]
+pub extern "C" fn get_all_counters_compressed(
+    collector: *mut std::ffi::c_void,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> bool {
+    if collector.is_null() || out_ptr.is_null() || out_len.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let collector = &*(collector as *mut MetricsCollector);
+        let values: Vec<u64> = collector.get_all_counters().into_values().collect();
+        let packed = streaming_integers::compress(&values);
+
+        *out_len = packed.len();
+        *out_ptr = Box::into_raw(packed.into_boxed_slice()) as *mut u8;
+        true
+    }
+}
+
+/// Add a value to a counter identified by a name plus a JSON object of labels, e.g.
+/// `{"method":"GET","status":"200"}`
+#[no_mangle]
+pub extern "C" fn add_counter_labeled(
+    collector: *mut std::ffi::c_void,
+    name: *const c_char,
+    labels_json: *const c_char,
+    value: u64,
+) {
+    if collector.is_null() || name.is_null() || labels_json.is_null() {
+        return;
+    }
+
+    unsafe {
+        let collector = &*(collector as *mut MetricsCollector);
+        let name_str = std::ffi::CStr::from_ptr(name).to_str().unwrap_or("");
+        let labels_str = std::ffi::CStr::from_ptr(labels_json).to_str().unwrap_or("{}");
+
+        let Ok(labels) = serde_json::from_str::<BTreeMap<String, String>>(labels_str) else {
+            return;
+        };
+
+        collector.add_counter_labeled(&Key::with_labels(name_str, labels), value);
+    }
+}
+
+/// Render all metrics in the Prometheus text exposition format
+#[no_mangle]
+pub extern "C" fn collector_render_prometheus(collector: *mut std::ffi::c_void) -> *const c_char {
+    if collector.is_null() {
+        return ptr::null();
+    }
+
+    unsafe {
+        let collector = &*(collector as *mut MetricsCollector);
+        match CString::new(collector.render_prometheus()) {
+            Ok(c_string) => c_string.into_raw() as *const c_char,
+            Err(_) => ptr::null(),
+        }
+    }
+}
+
 /// Reset all metrics
 #[no_mangle]
 pub extern "C" fn reset_all(collector: *mut std::ffi::c_void) {
@@ -1,15 +1,19 @@
 use parking_lot::RwLock;
 use dashmap::DashMap;
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_double, c_int, c_float};
 use std::ptr;
 use std::slice;
 use anyhow::Result;
+use rand::Rng;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
+use tokio::sync::{mpsc, oneshot};
 
 /// Vector storage with SIMD-optimized operations
 pub struct VectorEngine {
@@ -19,6 +23,17 @@ pub struct VectorEngine {
     config: EngineConfig,
     /// Performance statistics
     stats: Arc<RwLock<VectorStats>>,
+    /// Optional HNSW approximate-nearest-neighbor index over `vectors`
+    hnsw: Option<HnswIndex>,
+    /// Trained product-quantization codebook, populated once `pq_training_threshold` inserts
+    /// have accumulated when `storage_mode == StorageMode::Product`
+    pq_codebook: RwLock<Option<PqCodebook>>,
+    /// Live cache-size cap, mutable at runtime via `set_max_cache_size`
+    max_cache_size: AtomicUsize,
+    /// GPU compute context backing `SimilarityBackend::Gpu`, `None` when the `gpu` feature
+    /// is disabled or no usable device was found at construction time
+    #[cfg(feature = "gpu")]
+    gpu: Option<gpu::GpuContext>,
 }
 
 /// Configuration for the vector engine
@@ -30,13 +45,72 @@ pub struct EngineConfig {
     pub max_cache_size: usize,
     /// Use SIMD operations
     pub use_simd: bool,
+    /// Use an HNSW index instead of a brute-force scan in `find_similar`
+    pub use_hnsw: bool,
+    /// HNSW graph construction/search parameters
+    pub hnsw_params: HnswParams,
+    /// Vector compression/storage strategy
+    pub storage_mode: StorageMode,
+    /// Number of subspaces used by product quantization
+    pub pq_subvectors: usize,
+    /// Number of inserted vectors that triggers product-quantization codebook training
+    pub pq_training_threshold: usize,
+    /// Policy used to pick a victim once `max_cache_size` is exceeded
+    pub eviction_policy: EvictionPolicy,
+    /// Number of entries sampled per eviction instead of sweeping the whole map
+    pub eviction_sample_size: usize,
+    /// Compute backend used for batched similarity scoring
+    pub backend: SimilarityBackend,
+}
+
+/// Compute backend used for batched similarity scoring. `Gpu` requires the `gpu` feature
+/// and a usable device; both `VectorEngine::new` and `find_similar_batch` fall back to
+/// `Cpu` transparently when neither is available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimilarityBackend {
+    /// Existing SIMD/scalar CPU kernels
+    Cpu,
+    /// wgpu compute-shader backend over the whole stored corpus
+    Gpu,
+}
+
+/// Policy used to choose which entry to evict once `max_cache_size` is exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evict the entry with the oldest `last_accessed`
+    Lru,
+    /// Evict the entry with the lowest `access_count`
+    Lfu,
+    /// CLOCK/second-chance: give a referenced entry one more pass before evicting it
+    Clock,
+}
+
+/// Parameters controlling the HNSW graph built by [`HnswIndex`]
+#[derive(Debug, Clone, Copy)]
+pub struct HnswParams {
+    /// Maximum number of neighbors kept per node per layer
+    pub m: usize,
+    /// Size of the dynamic candidate list used while building the graph
+    pub ef_construction: usize,
+    /// Size of the dynamic candidate list used at query time
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 50,
+        }
+    }
 }
 
 /// Vector data with metadata
 #[derive(Debug, Clone)]
 pub struct VectorData {
-    /// The actual vector values
-    pub vector: Vec<f32>,
+    /// The stored vector, encoded per `EngineConfig::storage_mode`
+    storage: VectorStorage,
     /// Vector dimension
     pub dimension: usize,
     /// Creation timestamp
@@ -45,6 +119,171 @@ pub struct VectorData {
     pub last_accessed: Instant,
     /// Access count
     pub access_count: u64,
+    /// CLOCK/second-chance reference bit, set on every access and cleared when an eviction
+    /// sweep gives the entry its second chance
+    referenced: bool,
+}
+
+/// Vector compression/storage strategy selectable via `EngineConfig::storage_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageMode {
+    /// Full f32 precision (default)
+    Full,
+    /// int8 scalar quantization: one byte per dimension plus a per-vector min/scale
+    Scalar,
+    /// Product quantization: each vector is split into `pq_subvectors` contiguous
+    /// subspaces, each encoded as a single trained centroid id
+    Product,
+}
+
+/// In-memory representation of a stored vector, chosen by `EngineConfig::storage_mode`
+#[derive(Debug, Clone)]
+enum VectorStorage {
+    Full(Vec<f32>),
+    Scalar { codes: Vec<u8>, min: f32, scale: f32 },
+    Product { codes: Vec<u8> },
+}
+
+impl VectorStorage {
+    /// `round((v - min) / (max - min) * 255)` per dimension
+    fn quantize_scalar(vector: &[f32]) -> Self {
+        let min = vector.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = vector.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let scale = ((max - min) / 255.0).max(f32::EPSILON);
+
+        let codes = vector
+            .iter()
+            .map(|&v| (((v - min) / scale).round().clamp(0.0, 255.0)) as u8)
+            .collect();
+
+        VectorStorage::Scalar { codes, min, scale }
+    }
+
+    fn dequantize_scalar(codes: &[u8], min: f32, scale: f32) -> Vec<f32> {
+        codes.iter().map(|&c| min + c as f32 * scale).collect()
+    }
+}
+
+/// Trained product-quantization codebook: `m` subspaces, each with up to 256 centroids
+#[derive(Debug, Clone)]
+struct PqCodebook {
+    subvector_len: usize,
+    /// `[subspace][centroid_id] -> centroid vector`
+    centroids: Vec<Vec<Vec<f32>>>,
+}
+
+impl PqCodebook {
+    /// Train a codebook over `samples`, splitting each into `m` contiguous subvectors and
+    /// running k-means with `k = 256` centroids per subspace (fewer when there are fewer
+    /// than 256 samples: `k` is capped at `samples.len()`)
+    fn train(samples: &[Vec<f32>], m: usize) -> Option<Self> {
+        let dim = samples.first()?.len();
+        if m == 0 || dim % m != 0 {
+            return None;
+        }
+
+        let subvector_len = dim / m;
+        let k = 256.min(samples.len()).max(1);
+
+        let centroids = (0..m)
+            .map(|sub| {
+                let sub_samples: Vec<Vec<f32>> = samples
+                    .iter()
+                    .map(|v| v[sub * subvector_len..(sub + 1) * subvector_len].to_vec())
+                    .collect();
+
+                Self::kmeans(&sub_samples, k)
+            })
+            .collect();
+
+        Some(Self { subvector_len, centroids })
+    }
+
+    fn kmeans(samples: &[Vec<f32>], k: usize) -> Vec<Vec<f32>> {
+        let dim = samples[0].len();
+        let mut centroids: Vec<Vec<f32>> = (0..k).map(|i| samples[i % samples.len()].clone()).collect();
+
+        for _ in 0..10 {
+            let mut sums = vec![vec![0.0f32; dim]; k];
+            let mut counts = vec![0usize; k];
+
+            for sample in samples {
+                let nearest = (0..k)
+                    .min_by(|&a, &b| {
+                        Self::sq_dist(sample, &centroids[a])
+                            .partial_cmp(&Self::sq_dist(sample, &centroids[b]))
+                            .unwrap()
+                    })
+                    .unwrap_or(0);
+
+                counts[nearest] += 1;
+                for (s, &v) in sums[nearest].iter_mut().zip(sample.iter()) {
+                    *s += v;
+                }
+            }
+
+            for c in 0..k {
+                if counts[c] > 0 {
+                    for (v, &s) in centroids[c].iter_mut().zip(sums[c].iter()) {
+                        *v = s / counts[c] as f32;
+                    }
+                }
+            }
+        }
+
+        centroids
+    }
+
+    fn sq_dist(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b.iter()).map(|(&x, &y)| (x - y) * (x - y)).sum()
+    }
+
+    /// Encode a full vector into one centroid id per subspace
+    fn encode(&self, vector: &[f32]) -> Vec<u8> {
+        (0..self.centroids.len())
+            .map(|sub| {
+                let start = sub * self.subvector_len;
+                let sub_vec = &vector[start..start + self.subvector_len];
+
+                (0..self.centroids[sub].len())
+                    .min_by(|&a, &b| {
+                        Self::sq_dist(sub_vec, &self.centroids[sub][a])
+                            .partial_cmp(&Self::sq_dist(sub_vec, &self.centroids[sub][b]))
+                            .unwrap()
+                    })
+                    .unwrap_or(0) as u8
+            })
+            .collect()
+    }
+
+    /// Reconstruct the approximate vector from its codes
+    fn decode(&self, codes: &[u8]) -> Vec<f32> {
+        codes
+            .iter()
+            .enumerate()
+            .flat_map(|(sub, &code)| self.centroids[sub][code as usize].clone())
+            .collect()
+    }
+
+    /// Precompute an `m x 256` table of partial distances between `query` and every centroid
+    fn distance_table(&self, query: &[f32]) -> Vec<Vec<f32>> {
+        (0..self.centroids.len())
+            .map(|sub| {
+                let start = sub * self.subvector_len;
+                let sub_query = &query[start..start + self.subvector_len];
+
+                self.centroids[sub]
+                    .iter()
+                    .map(|centroid| Self::sq_dist(sub_query, centroid))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Sum the looked-up partial distances for a stored code using a precomputed table
+    fn asymmetric_distance(&self, table: &[Vec<f32>], codes: &[u8]) -> f32 {
+        codes.iter().enumerate().map(|(sub, &code)| table[sub][code as usize]).sum()
+    }
 }
 
 /// Performance statistics
@@ -60,6 +299,104 @@ pub struct VectorStats {
     pub avg_latency_ns: u64,
     /// Operations per second
     pub ops_per_sec: u64,
+    /// Entries evicted to enforce `max_cache_size`
+    pub evictions: u64,
+    /// Streaming approximate-quantile summary of operation latencies
+    latency_summary: GkSummary,
+}
+
+/// A single entry in a Greenwald-Khanna epsilon-approximate quantile summary: `value` plus
+/// the `[rmin, rmax]` bracket on its possible rank among all observations so far
+#[derive(Debug, Clone)]
+struct GkTuple {
+    value: u64,
+    rmin: u64,
+    rmax: u64,
+}
+
+/// Bounded-memory streaming quantile sketch used to report latency percentiles without
+/// storing every sample. Size stays `O((1/epsilon) log(epsilon*N))` via periodic compression.
+#[derive(Debug, Clone)]
+struct GkSummary {
+    epsilon: f64,
+    count: u64,
+    entries: Vec<GkTuple>,
+}
+
+impl Default for GkSummary {
+    fn default() -> Self {
+        Self::new(0.01)
+    }
+}
+
+impl GkSummary {
+    fn new(epsilon: f64) -> Self {
+        Self {
+            epsilon,
+            count: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Insert a new observation, bracketing its rank from its sorted neighbors
+    fn insert(&mut self, value: u64) {
+        self.count += 1;
+
+        let pos = self.entries.partition_point(|t| t.value < value);
+
+        let (rmin, rmax) = if self.entries.is_empty() {
+            (1, 1)
+        } else if pos == 0 {
+            (1, self.entries[0].rmax)
+        } else if pos == self.entries.len() {
+            (self.entries[pos - 1].rmin + 1, self.count)
+        } else {
+            (self.entries[pos - 1].rmin + 1, self.entries[pos].rmax)
+        };
+
+        self.entries.insert(pos, GkTuple { value, rmin, rmax });
+
+        let compress_every = (1.0 / (2.0 * self.epsilon)).ceil().max(1.0) as u64;
+        if self.count % compress_every == 0 {
+            self.compress();
+        }
+    }
+
+    /// Drop entries whose rank bracket is tight enough relative to `2*epsilon*N` that the
+    /// neighbor can safely absorb its rank range
+    fn compress(&mut self) {
+        if self.entries.len() < 3 {
+            return;
+        }
+
+        let threshold = (2.0 * self.epsilon * self.count as f64) as u64;
+        let mut i = 1;
+        while i + 1 < self.entries.len() {
+            let band = self.entries[i].rmax - self.entries[i].rmin;
+            if band <= threshold {
+                let removed = self.entries.remove(i);
+                self.entries[i].rmin = self.entries[i].rmin.min(removed.rmin);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// First stored value whose `rmax` bracket satisfies `rmax >= ceil(phi*N) - epsilon*N`
+    fn quantile(&self, phi: f64) -> Option<u64> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let n = self.count as f64;
+        let target = (phi * n).ceil() - self.epsilon * n;
+
+        self.entries
+            .iter()
+            .find(|t| t.rmax as f64 >= target)
+            .or_else(|| self.entries.last())
+            .map(|t| t.value)
+    }
 }
 
 /// Similarity search result
@@ -92,6 +429,14 @@ impl Default for EngineConfig {
             default_dimension: 768, // Common embedding size
             max_cache_size: 100_000,
             use_simd: true,
+            use_hnsw: false,
+            hnsw_params: HnswParams::default(),
+            storage_mode: StorageMode::Full,
+            pq_subvectors: 8,
+            pq_training_threshold: 1000,
+            eviction_policy: EvictionPolicy::Lru,
+            eviction_sample_size: 32,
+            backend: SimilarityBackend::Cpu,
         }
     }
 }
@@ -99,10 +444,30 @@ impl Default for EngineConfig {
 impl VectorEngine {
     /// Create a new vector engine
     pub fn new(config: EngineConfig) -> Self {
+        let hnsw = if config.use_hnsw {
+            Some(HnswIndex::new(config.hnsw_params))
+        } else {
+            None
+        };
+
+        let max_cache_size = AtomicUsize::new(config.max_cache_size);
+
+        #[cfg(feature = "gpu")]
+        let gpu = if config.backend == SimilarityBackend::Gpu {
+            gpu::GpuContext::try_new()
+        } else {
+            None
+        };
+
         Self {
             vectors: DashMap::new(),
             config,
             stats: Arc::new(RwLock::new(VectorStats::default())),
+            hnsw,
+            pq_codebook: RwLock::new(None),
+            max_cache_size,
+            #[cfg(feature = "gpu")]
+            gpu,
         }
     }
 
@@ -115,16 +480,39 @@ impl VectorEngine {
             return Err(VectorError::InvalidVectorSize { size: 0 }.into());
         }
 
+        let storage = self.encode_vector(&vector);
+
         let vector_data = VectorData {
+            storage,
             dimension: vector.len(),
             created_at: Instant::now(),
             last_accessed: Instant::now(),
             access_count: 0,
-            vector,
+            referenced: false,
         };
 
         self.vectors.insert(id.clone(), vector_data);
 
+        if let Some(hnsw) = &self.hnsw {
+            hnsw.insert(&id, &vector, self);
+        }
+
+        if self.config.storage_mode == StorageMode::Product {
+            self.maybe_train_pq();
+        }
+
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = &self.gpu {
+            gpu.mark_dirty();
+        }
+
+        let cap = self.max_cache_size.load(Ordering::Relaxed);
+        while self.vectors.len() > cap {
+            if !self.evict_one() {
+                break;
+            }
+        }
+
         // Update stats
         let mut stats = self.stats.write();
         stats.total_ops += 1;
@@ -133,15 +521,74 @@ impl VectorEngine {
         Ok(())
     }
 
+    /// Encode a full-precision vector per `EngineConfig::storage_mode`
+    fn encode_vector(&self, vector: &[f32]) -> VectorStorage {
+        match self.config.storage_mode {
+            StorageMode::Full => VectorStorage::Full(vector.to_vec()),
+            StorageMode::Scalar => VectorStorage::quantize_scalar(vector),
+            StorageMode::Product => match self.pq_codebook.read().as_ref() {
+                Some(codebook) => VectorStorage::Product {
+                    codes: codebook.encode(vector),
+                },
+                // Codebook isn't trained yet: hold full precision until training completes
+                None => VectorStorage::Full(vector.to_vec()),
+            },
+        }
+    }
+
+    /// Decode a stored representation back into an approximate (or exact) f32 vector
+    fn decode(&self, storage: &VectorStorage) -> Vec<f32> {
+        match storage {
+            VectorStorage::Full(v) => v.clone(),
+            VectorStorage::Scalar { codes, min, scale } => {
+                VectorStorage::dequantize_scalar(codes, *min, *scale)
+            }
+            VectorStorage::Product { codes } => self
+                .pq_codebook
+                .read()
+                .as_ref()
+                .map(|codebook| codebook.decode(codes))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Train the product-quantization codebook once enough vectors have accumulated, then
+    /// re-encode every already-stored vector with it
+    fn maybe_train_pq(&self) {
+        if self.pq_codebook.read().is_some() {
+            return;
+        }
+
+        if self.vectors.len() < self.config.pq_training_threshold {
+            return;
+        }
+
+        let samples: Vec<Vec<f32>> = self.vectors.iter().map(|e| self.decode(&e.storage)).collect();
+
+        if let Some(codebook) = PqCodebook::train(&samples, self.config.pq_subvectors) {
+            for mut entry in self.vectors.iter_mut() {
+                let codes = codebook.encode(&self.decode(&entry.storage));
+                entry.storage = VectorStorage::Product { codes };
+            }
+
+            *self.pq_codebook.write() = Some(codebook);
+        }
+    }
+
+    /// Return the approximate decompressed vector stored under `id`
+    pub fn reconstruct(&self, id: &str) -> Option<Vec<f32>> {
+        self.vectors.get(id).map(|entry| self.decode(&entry.storage))
+    }
+
     /// Get a vector by ID
     pub fn get(&self, id: &str) -> Option<Vec<f32>> {
         let start = Instant::now();
 
-        let result = self.vectors.get(id).map(|entry| {
-            let mut data = entry.value().clone();
-            data.access_count += 1;
-            data.last_accessed = Instant::now();
-            data.vector.clone()
+        let result = self.vectors.get_mut(id).map(|mut entry| {
+            entry.access_count += 1;
+            entry.last_accessed = Instant::now();
+            entry.referenced = true;
+            self.decode(&entry.storage)
         });
 
         // Update stats
@@ -236,9 +683,83 @@ impl VectorEngine {
     }
 
     /// Find similar vectors
+    ///
+    /// Uses the HNSW index when `EngineConfig::use_hnsw` is enabled, falling back to a
+    /// brute-force scan otherwise.
     pub fn find_similar(&self, query: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
         let start = Instant::now();
 
+        let results = if let Some(hnsw) = &self.hnsw {
+            self.find_similar_hnsw(hnsw, query, limit)?
+        } else {
+            self.find_similar_scan(query, limit)?
+        };
+
+        // Update stats
+        let mut stats = self.stats.write();
+        stats.total_ops += 1;
+        stats.update_latency(start.elapsed());
+
+        Ok(results)
+    }
+
+    /// Score many queries against the stored corpus in one call, amortizing the cost of
+    /// preparing the corpus (a single decode pass on CPU, a single device upload on GPU)
+    /// across all of them instead of repeating it per `find_similar` call.
+    ///
+    /// Dispatches to `SimilarityBackend::Gpu` when configured and a device was acquired at
+    /// construction time, falling back to the CPU/SIMD path otherwise.
+    pub fn find_similar_batch(
+        &self,
+        queries: &[Vec<f32>],
+        limit: usize,
+    ) -> Result<Vec<Vec<SearchResult>>> {
+        #[cfg(feature = "gpu")]
+        if self.config.backend == SimilarityBackend::Gpu {
+            if let Some(gpu) = &self.gpu {
+                return Ok(gpu.find_similar_batch(self, queries, limit));
+            }
+        }
+
+        let corpus: Vec<(String, Vec<f32>)> = self
+            .vectors
+            .iter()
+            .map(|entry| (entry.key().clone(), self.decode(&entry.storage)))
+            .collect();
+
+        Ok(queries
+            .iter()
+            .map(|query| {
+                let mut scored: Vec<SearchResult> = corpus
+                    .iter()
+                    .filter(|(_, vector)| vector.len() == query.len())
+                    .filter_map(|(id, vector)| {
+                        let score = self.cosine_similarity(query, vector).ok()?;
+                        (score > 0.0).then(|| SearchResult {
+                            id: id.clone(),
+                            score,
+                            vector: vector.clone(),
+                        })
+                    })
+                    .collect();
+
+                scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+                scored.truncate(limit);
+                scored
+            })
+            .collect())
+    }
+
+    /// Brute-force linear scan over all stored vectors
+    fn find_similar_scan(&self, query: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        // When product-quantized, score directly against the codes via a precomputed
+        // per-query distance table instead of decoding every stored vector first.
+        let pq_table = if self.config.storage_mode == StorageMode::Product {
+            self.pq_codebook.read().as_ref().map(|codebook| (codebook.clone(), codebook.distance_table(query)))
+        } else {
+            None
+        };
+
         let mut results = Vec::new();
 
         for entry in self.vectors.iter() {
@@ -248,14 +769,33 @@ impl VectorEngine {
                 continue;
             }
 
-            let similarity = self.cosine_similarity(query, &vector_data.vector)?;
-
-            if similarity > 0.0 { // Only include positive similarities
-                results.push(SearchResult {
-                    id: entry.key().clone(),
-                    score: similarity,
-                    vector: vector_data.vector.clone(),
-                });
+            match (&pq_table, &vector_data.storage) {
+                (Some((codebook, table)), VectorStorage::Product { codes }) => {
+                    // `asymmetric_distance` is a summed squared-Euclidean distance over the PQ
+                    // codebook's sub-centroids, not a value on the `1.0 - cosine_similarity`
+                    // scale the rest of this engine uses (HNSW, `cosine_similarity`) - there's
+                    // no way to convert one into the other without the original vectors'
+                    // norms, which quantization discards. Rank directly by that native
+                    // distance instead of forcing it onto the cosine scale: smaller distance
+                    // is still "more similar," there's just no zero-point to filter against.
+                    let distance = codebook.asymmetric_distance(table, codes);
+                    results.push(SearchResult {
+                        id: entry.key().clone(),
+                        score: -distance,
+                        vector: codebook.decode(codes),
+                    });
+                }
+                _ => {
+                    let decoded = self.decode(&vector_data.storage);
+                    let similarity = self.cosine_similarity(query, &decoded)?;
+                    if similarity > 0.0 { // Only include positive similarities
+                        results.push(SearchResult {
+                            id: entry.key().clone(),
+                            score: similarity,
+                            vector: decoded,
+                        });
+                    }
+                }
             }
         }
 
@@ -265,10 +805,32 @@ impl VectorEngine {
         // Limit results
         results.truncate(limit);
 
-        // Update stats
-        let mut stats = self.stats.write();
-        stats.total_ops += 1;
-        stats.update_latency(start.elapsed());
+        Ok(results)
+    }
+
+    /// HNSW-backed approximate search
+    fn find_similar_hnsw(
+        &self,
+        hnsw: &HnswIndex,
+        query: &[f32],
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let ef = hnsw.params.ef_search.max(limit);
+        let candidates = hnsw.search(query, ef, self);
+
+        let mut results: Vec<SearchResult> = candidates
+            .into_iter()
+            .filter_map(|(id, distance)| {
+                self.vectors.get(&id).map(|entry| SearchResult {
+                    id,
+                    score: 1.0 - distance,
+                    vector: self.decode(&entry.storage),
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results.truncate(limit);
 
         Ok(results)
     }
@@ -426,13 +988,82 @@ impl VectorEngine {
 
     /// Remove a vector by ID
     pub fn remove(&self, id: &str) -> bool {
-        self.vectors.remove(id).is_some()
+        let removed = self.vectors.remove(id).is_some();
+
+        if removed {
+            if let Some(hnsw) = &self.hnsw {
+                hnsw.remove(id);
+            }
+
+            #[cfg(feature = "gpu")]
+            if let Some(gpu) = &self.gpu {
+                gpu.mark_dirty();
+            }
+        }
+
+        removed
     }
 
     /// List all vector IDs
     pub fn list_ids(&self) -> Vec<String> {
         self.vectors.iter().map(|entry| entry.key().clone()).collect()
     }
+
+    /// Update the enforced cache-size cap at runtime
+    pub fn set_max_cache_size(&self, new_max: usize) {
+        self.max_cache_size.store(new_max, Ordering::Relaxed);
+    }
+
+    /// Evict a single entry per `EngineConfig::eviction_policy`, scanning only a bounded
+    /// sample of the map rather than sweeping the whole `DashMap`
+    fn evict_one(&self) -> bool {
+        let sample_size = self.config.eviction_sample_size.max(1);
+
+        let evict_id = match self.config.eviction_policy {
+            EvictionPolicy::Lru => self
+                .vectors
+                .iter()
+                .take(sample_size)
+                .min_by_key(|e| e.last_accessed)
+                .map(|e| e.key().clone()),
+            EvictionPolicy::Lfu => self
+                .vectors
+                .iter()
+                .take(sample_size)
+                .min_by_key(|e| e.access_count)
+                .map(|e| e.key().clone()),
+            EvictionPolicy::Clock => self.evict_clock(sample_size),
+        };
+
+        let Some(id) = evict_id else { return false };
+
+        self.vectors.remove(&id);
+        if let Some(hnsw) = &self.hnsw {
+            hnsw.remove(&id);
+        }
+
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = &self.gpu {
+            gpu.mark_dirty();
+        }
+
+        self.stats.write().evictions += 1;
+
+        true
+    }
+
+    /// Sweep the sample clearing reference bits along the way, evicting the first entry
+    /// whose bit is already clear (a "second chance" for recently-referenced entries)
+    fn evict_clock(&self, sample_size: usize) -> Option<String> {
+        for mut entry in self.vectors.iter_mut().take(sample_size) {
+            if !entry.referenced {
+                return Some(entry.key().clone());
+            }
+            entry.referenced = false;
+        }
+
+        self.vectors.iter().take(1).next().map(|e| e.key().clone())
+    }
 }
 
 impl VectorStats {
@@ -440,12 +1071,33 @@ impl VectorStats {
     fn update_latency(&mut self, latency: Duration) {
         let latency_ns = latency.as_nanos() as u64;
         self.avg_latency_ns = (self.avg_latency_ns + latency_ns) / 2;
+        self.latency_summary.insert(latency_ns);
 
         // Update ops per second (simplified)
         if self.total_ops > 0 {
             self.ops_per_sec = 1_000_000_000 / latency_ns;
         }
     }
+
+    /// Approximate latency at quantile `phi` (e.g. 0.99 for p99), in nanoseconds
+    pub fn quantile(&self, phi: f64) -> u64 {
+        self.latency_summary.quantile(phi).unwrap_or(0)
+    }
+
+    /// p50 operation latency in nanoseconds
+    pub fn p50_ns(&self) -> u64 {
+        self.quantile(0.5)
+    }
+
+    /// p95 operation latency in nanoseconds
+    pub fn p95_ns(&self) -> u64 {
+        self.quantile(0.95)
+    }
+
+    /// p99 operation latency in nanoseconds
+    pub fn p99_ns(&self) -> u64 {
+        self.quantile(0.99)
+    }
 }
 
 impl Clone for VectorStats {
@@ -456,6 +1108,736 @@ impl Clone for VectorStats {
             cache_misses: self.cache_misses,
             avg_latency_ns: self.avg_latency_ns,
             ops_per_sec: self.ops_per_sec,
+            evictions: self.evictions,
+            latency_summary: self.latency_summary.clone(),
+        }
+    }
+}
+
+/// Core vector-store operations, implemented synchronously on `VectorEngine` and mirrored
+/// asynchronously by `AsyncVectorEngine`.
+pub trait VectorStore {
+    fn insert(&self, id: String, vector: Vec<f32>) -> Result<()>;
+    fn get(&self, id: &str) -> Option<Vec<f32>>;
+    fn find_similar(&self, query: &[f32], limit: usize) -> Result<Vec<SearchResult>>;
+    fn remove(&self, id: &str) -> bool;
+}
+
+impl VectorStore for VectorEngine {
+    fn insert(&self, id: String, vector: Vec<f32>) -> Result<()> {
+        VectorEngine::insert(self, id, vector)
+    }
+
+    fn get(&self, id: &str) -> Option<Vec<f32>> {
+        VectorEngine::get(self, id)
+    }
+
+    fn find_similar(&self, query: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        VectorEngine::find_similar(self, query, limit)
+    }
+
+    fn remove(&self, id: &str) -> bool {
+        VectorEngine::remove(self, id)
+    }
+}
+
+/// Async facade over `VectorEngine` for use inside Tokio-based services.
+///
+/// CPU-heavy operations (`insert`, `find_similar`, `remove`) run on a background thread
+/// and resolve through a `oneshot`, keeping the hot path off the async executor's worker
+/// threads; cheap metadata reads (`len`, `list_ids`, `get_stats`) resolve inline since they
+/// only touch already-synchronized in-memory state.
+#[derive(Clone)]
+pub struct AsyncVectorEngine {
+    inner: Arc<VectorEngine>,
+}
+
+impl AsyncVectorEngine {
+    /// Wrap an existing engine for async access; cloning `AsyncVectorEngine` shares the
+    /// same underlying `VectorEngine`.
+    pub fn new(inner: Arc<VectorEngine>) -> Self {
+        Self { inner }
+    }
+
+    pub async fn insert(&self, id: String, vector: Vec<f32>) -> Result<()> {
+        let engine = self.inner.clone();
+        let (tx, rx) = oneshot::channel();
+
+        std::thread::spawn(move || {
+            let _ = tx.send(engine.insert(id, vector));
+        });
+
+        rx.await.map_err(|_| VectorError::ComputationError {
+            message: "insert worker thread dropped before completion".to_string(),
+        })?
+    }
+
+    pub async fn get(&self, id: String) -> Option<Vec<f32>> {
+        let engine = self.inner.clone();
+        let (tx, rx) = oneshot::channel();
+
+        std::thread::spawn(move || {
+            let _ = tx.send(engine.get(&id));
+        });
+
+        rx.await.unwrap_or(None)
+    }
+
+    pub async fn find_similar(&self, query: Vec<f32>, limit: usize) -> Result<Vec<SearchResult>> {
+        let engine = self.inner.clone();
+        let (tx, rx) = oneshot::channel();
+
+        std::thread::spawn(move || {
+            let _ = tx.send(engine.find_similar(&query, limit));
+        });
+
+        rx.await.map_err(|_| VectorError::ComputationError {
+            message: "find_similar worker thread dropped before completion".to_string(),
+        })?
+    }
+
+    pub async fn remove(&self, id: String) -> bool {
+        let engine = self.inner.clone();
+        let (tx, rx) = oneshot::channel();
+
+        std::thread::spawn(move || {
+            let _ = tx.send(engine.remove(&id));
+        });
+
+        rx.await.unwrap_or(false)
+    }
+
+    /// Number of stored vectors; resolves inline, no worker thread needed.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn list_ids(&self) -> Vec<String> {
+        self.inner.list_ids()
+    }
+
+    pub fn get_stats(&self) -> VectorStats {
+        self.inner.get_stats()
+    }
+
+    /// Stream similar vectors through an `mpsc` channel as they clear `score_threshold`,
+    /// rather than blocking the caller until the full top-k scan completes.
+    pub fn find_similar_stream(
+        &self,
+        query: Vec<f32>,
+        limit: usize,
+        score_threshold: f32,
+    ) -> mpsc::Receiver<SearchResult> {
+        let (tx, rx) = mpsc::channel(limit.max(1));
+        let engine = self.inner.clone();
+
+        std::thread::spawn(move || {
+            if let Ok(results) = engine.find_similar(&query, limit) {
+                for result in results {
+                    if result.score >= score_threshold && tx.blocking_send(result).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+/// GPU compute-shader backend for `find_similar_batch`, gated behind the `gpu` feature so
+/// the default build carries no wgpu dependency.
+#[cfg(feature = "gpu")]
+mod gpu {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    /// WGSL mirror of `VectorEngine::cosine_similarity_scalar`: one invocation per stored
+    /// vector, computing dot product and both norms over the `corpus`/`query` buffers.
+    const COSINE_SIMILARITY_SHADER: &str = r#"
+        struct Params { dim: u32, count: u32 }
+
+        @group(0) @binding(0) var<storage, read> corpus: array<f32>;
+        @group(0) @binding(1) var<storage, read> query: array<f32>;
+        @group(0) @binding(2) var<storage, read_write> scores: array<f32>;
+        @group(0) @binding(3) var<uniform> params: Params;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+            let i = gid.x;
+            if (i >= params.count) { return; }
+
+            var dot: f32 = 0.0;
+            var norm_a: f32 = 0.0;
+            var norm_b: f32 = 0.0;
+            for (var d: u32 = 0u; d < params.dim; d = d + 1u) {
+                let a = corpus[d * params.count + i];
+                let b = query[d];
+                dot = dot + a * b;
+                norm_a = norm_a + a * a;
+                norm_b = norm_b + b * b;
+            }
+
+            let denom = sqrt(norm_a * norm_b);
+            scores[i] = select(dot / denom, 0.0, denom == 0.0);
+        }
+    "#;
+
+    /// Layout of the `params` uniform consumed by `COSINE_SIMILARITY_SHADER`.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+    struct ShaderParams {
+        dim: u32,
+        count: u32,
+    }
+
+    /// Device-resident, dimension-major copy of the stored corpus plus the compute
+    /// pipeline that scores a query against every stored vector in one dispatch. Kept in
+    /// sync with `insert`/`remove` via a dirty flag and lazy re-upload rather than
+    /// re-uploading on every query.
+    pub(crate) struct GpuContext {
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        pipeline: wgpu::ComputePipeline,
+        bind_group_layout: wgpu::BindGroupLayout,
+        corpus_ids: RwLock<Vec<String>>,
+        corpus_buffer: RwLock<Option<wgpu::Buffer>>,
+        /// Dimension shared by every vector currently uploaded in `corpus_buffer`; stored
+        /// vectors whose dimension disagrees with the first one seen during `sync` are
+        /// left out of the device-resident corpus, mirroring the CPU path's per-query
+        /// `vector.len() == query.len()` filter.
+        dim: AtomicUsize,
+        dirty: AtomicBool,
+    }
+
+    impl GpuContext {
+        /// Request an adapter and device; returns `None` (triggering a transparent
+        /// CPU fallback in `VectorEngine::new`) when no adapter is available.
+        pub(crate) fn try_new() -> Option<Self> {
+            let instance = wgpu::Instance::default();
+            let adapter = pollster::block_on(
+                instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+            )?;
+            let (device, queue) = pollster::block_on(
+                adapter.request_device(&wgpu::DeviceDescriptor::default(), None),
+            )
+            .ok()?;
+
+            let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("agenticgen-cosine-similarity"),
+                source: wgpu::ShaderSource::Wgsl(COSINE_SIMILARITY_SHADER.into()),
+            });
+
+            let storage_entry = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            };
+
+            let bind_group_layout =
+                device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("agenticgen-cosine-similarity-layout"),
+                    entries: &[
+                        storage_entry(0, true),
+                        storage_entry(1, true),
+                        storage_entry(2, false),
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 3,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ],
+                });
+
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("agenticgen-cosine-similarity-pipeline-layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+            let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("agenticgen-cosine-similarity-pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: "main",
+            });
+
+            Some(Self {
+                device,
+                queue,
+                pipeline,
+                bind_group_layout,
+                corpus_ids: RwLock::new(Vec::new()),
+                corpus_buffer: RwLock::new(None),
+                dim: AtomicUsize::new(0),
+                dirty: AtomicBool::new(true),
+            })
+        }
+
+        /// Mark the device-resident corpus stale; the next batch re-uploads it lazily.
+        pub(crate) fn mark_dirty(&self) {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+
+        /// Re-upload the full corpus, laid out dimension-major, if it changed since the
+        /// last batch.
+        fn sync(&self, engine: &VectorEngine) {
+            if !self.dirty.swap(false, Ordering::Relaxed) {
+                return;
+            }
+
+            let mut ids = Vec::new();
+            let mut rows: Vec<Vec<f32>> = Vec::new();
+            let mut dim = 0usize;
+            for entry in engine.vectors.iter() {
+                let vector = engine.decode(&entry.storage);
+                if dim == 0 {
+                    dim = vector.len();
+                }
+                if vector.len() != dim {
+                    continue;
+                }
+                ids.push(entry.key().clone());
+                rows.push(vector);
+            }
+
+            // Dimension-major: component `d` of stored vector `i` sits at `d * count + i`
+            let flat: Vec<f32> = (0..dim).flat_map(|d| rows.iter().map(move |v| v[d])).collect();
+
+            use wgpu::util::DeviceExt;
+            let buffer = (!flat.is_empty()).then(|| {
+                self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("agenticgen-vector-corpus"),
+                    contents: bytemuck::cast_slice(&flat),
+                    usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                })
+            });
+
+            self.dim.store(dim, Ordering::Relaxed);
+            *self.corpus_buffer.write() = buffer;
+            *self.corpus_ids.write() = ids;
+        }
+
+        /// Score every query against the synced corpus, reusing the same device buffer
+        /// across all of them.
+        pub(crate) fn find_similar_batch(
+            &self,
+            engine: &VectorEngine,
+            queries: &[Vec<f32>],
+            limit: usize,
+        ) -> Vec<Vec<SearchResult>> {
+            self.sync(engine);
+            queries
+                .iter()
+                .map(|query| self.dispatch(engine, query, limit))
+                .collect()
+        }
+
+        /// Run the compute shader for a single query against the synced corpus and read
+        /// the score array back for host-side top-k selection.
+        fn dispatch(&self, engine: &VectorEngine, query: &[f32], limit: usize) -> Vec<SearchResult> {
+            let ids = self.corpus_ids.read();
+            let dim = self.dim.load(Ordering::Relaxed);
+            let corpus_buffer = self.corpus_buffer.read();
+            let Some(corpus_buffer) = corpus_buffer.as_ref() else {
+                return Vec::new();
+            };
+            if ids.is_empty() || dim == 0 || query.len() != dim {
+                return Vec::new();
+            }
+
+            let count = ids.len() as u32;
+
+            use wgpu::util::DeviceExt;
+            let query_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("agenticgen-query"),
+                contents: bytemuck::cast_slice(query),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+
+            let params = ShaderParams { dim: dim as u32, count };
+            let params_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("agenticgen-params"),
+                contents: bytemuck::bytes_of(&params),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+            let scores_size = (count as u64) * std::mem::size_of::<f32>() as u64;
+            let scores_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("agenticgen-scores"),
+                size: scores_size,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+
+            let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("agenticgen-scores-readback"),
+                size: scores_size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("agenticgen-cosine-similarity-bind-group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: corpus_buffer.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 1, resource: query_buffer.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 2, resource: scores_buffer.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 3, resource: params_buffer.as_entire_binding() },
+                ],
+            });
+
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("agenticgen-cosine-similarity-encoder"),
+            });
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("agenticgen-cosine-similarity-pass"),
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups((count + 63) / 64, 1, 1);
+            }
+            encoder.copy_buffer_to_buffer(&scores_buffer, 0, &readback_buffer, 0, scores_size);
+            self.queue.submit(Some(encoder.finish()));
+
+            let slice = readback_buffer.slice(..);
+            let (tx, rx) = std::sync::mpsc::channel();
+            slice.map_async(wgpu::MapMode::Read, move |result| {
+                let _ = tx.send(result);
+            });
+            self.device.poll(wgpu::Maintain::Wait);
+            let Ok(Ok(())) = rx.recv() else {
+                return Vec::new();
+            };
+
+            let scores: Vec<f32> = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+            readback_buffer.unmap();
+
+            let mut results: Vec<SearchResult> = ids
+                .iter()
+                .zip(scores)
+                .filter(|(_, score)| *score > 0.0)
+                .filter_map(|(id, score)| {
+                    let vector = engine.reconstruct(id)?;
+                    Some(SearchResult { id: id.clone(), score, vector })
+                })
+                .collect();
+
+            results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+            results.truncate(limit);
+            results
+        }
+    }
+}
+
+/// A single node's per-layer neighbor lists in the HNSW graph
+#[derive(Debug, Clone, Default)]
+struct HnswNode {
+    /// Highest layer this node participates in
+    max_layer: usize,
+    /// Neighbor ids at each layer, indexed from layer 0 up to `max_layer`
+    layers: Vec<Vec<String>>,
+}
+
+/// Orders `(distance, id)` pairs by distance for use in a `BinaryHeap`
+#[derive(Debug, Clone, PartialEq)]
+struct ScoredId(f32, String);
+
+impl Eq for ScoredId {}
+
+impl PartialOrd for ScoredId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+/// Hierarchical Navigable Small World index, providing logarithmic-ish approximate
+/// nearest-neighbor search over the vectors stored in a [`VectorEngine`]
+struct HnswIndex {
+    /// Per-node layer adjacency, parallel to `VectorEngine::vectors`
+    nodes: DashMap<String, HnswNode>,
+    /// Current top-layer entry point
+    entry_point: RwLock<Option<String>>,
+    /// Graph construction/search parameters
+    params: HnswParams,
+}
+
+impl HnswIndex {
+    fn new(params: HnswParams) -> Self {
+        Self {
+            nodes: DashMap::new(),
+            entry_point: RwLock::new(None),
+            params,
+        }
+    }
+
+    /// Distance between two vectors under the engine's similarity metric (smaller = closer)
+    fn distance(&self, engine: &VectorEngine, a: &[f32], b: &[f32]) -> f32 {
+        1.0 - engine.cosine_similarity(a, b).unwrap_or(0.0)
+    }
+
+    /// Draw a random max layer: `floor(-ln(rand(0,1)) * mL)` with `mL = 1 / ln(M)`
+    fn random_level(&self) -> usize {
+        let m_l = 1.0 / (self.params.m.max(2) as f64).ln();
+        let r: f64 = rand::thread_rng().gen_range(f64::MIN_POSITIVE..1.0);
+        (-r.ln() * m_l).floor() as usize
+    }
+
+    /// Insert `id` into the graph, wiring it into every layer from its assigned level down to 0
+    fn insert(&self, id: &str, vector: &[f32], engine: &VectorEngine) {
+        let level = self.random_level();
+        let mut node = HnswNode {
+            max_layer: level,
+            layers: vec![Vec::new(); level + 1],
+        };
+
+        let entry = self.entry_point.read().clone();
+        let Some(mut current) = entry else {
+            self.nodes.insert(id.to_string(), node);
+            *self.entry_point.write() = Some(id.to_string());
+            return;
+        };
+
+        let top_layer = self.nodes.get(&current).map(|n| n.max_layer).unwrap_or(0);
+
+        // Greedy descent down to the node's own top layer
+        for layer in ((level + 1)..=top_layer).rev() {
+            current = self.greedy_search(&current, vector, layer, engine);
+        }
+
+        // Best-first search + heuristic neighbor selection from `level` down to 0
+        for layer in (0..=level.min(top_layer)).rev() {
+            let candidates = self.search_layer(&current, vector, layer, self.params.ef_construction, engine);
+            let selected = self.select_neighbors(vector, candidates, self.params.m, engine);
+
+            node.layers[layer] = selected.iter().map(|(id, _)| id.clone()).collect();
+
+            for (neighbor_id, _) in &selected {
+                self.connect(neighbor_id, id, layer, engine);
+            }
+
+            if let Some((best, _)) = selected.first() {
+                current = best.clone();
+            }
+        }
+
+        self.nodes.insert(id.to_string(), node);
+
+        if level > top_layer {
+            *self.entry_point.write() = Some(id.to_string());
+        }
+    }
+
+    /// Walk from `entry` to the single closest neighbor at `layer`, repeating until no
+    /// neighbor improves on the current node
+    fn greedy_search(&self, entry: &str, query: &[f32], layer: usize, engine: &VectorEngine) -> String {
+        let mut current = entry.to_string();
+        let mut current_dist = engine
+            .vectors
+            .get(&current)
+            .map(|e| self.distance(engine, query, &engine.decode(&e.storage)))
+            .unwrap_or(f32::MAX);
+
+        loop {
+            let mut improved = false;
+
+            if let Some(node) = self.nodes.get(&current) {
+                if let Some(neighbors) = node.layers.get(layer) {
+                    for neighbor in neighbors {
+                        if let Some(v) = engine.vectors.get(neighbor) {
+                            let d = self.distance(engine, query, &engine.decode(&v.storage));
+                            if d < current_dist {
+                                current_dist = d;
+                                current = neighbor.clone();
+                                improved = true;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !improved {
+                break;
+            }
+        }
+
+        current
+    }
+
+    /// Best-first search at `layer` with a dynamic candidate set bounded by `ef`
+    fn search_layer(
+        &self,
+        entry: &str,
+        query: &[f32],
+        layer: usize,
+        ef: usize,
+        engine: &VectorEngine,
+    ) -> Vec<(String, f32)> {
+        let mut visited: HashSet<String> = HashSet::new();
+        visited.insert(entry.to_string());
+
+        let entry_dist = engine
+            .vectors
+            .get(entry)
+            .map(|e| self.distance(engine, query, &engine.decode(&e.storage)))
+            .unwrap_or(f32::MAX);
+
+        let mut frontier = BinaryHeap::new();
+        frontier.push(Reverse(ScoredId(entry_dist, entry.to_string())));
+
+        let mut found: Vec<(String, f32)> = vec![(entry.to_string(), entry_dist)];
+
+        while let Some(Reverse(ScoredId(dist, id))) = frontier.pop() {
+            if found.len() >= ef {
+                let worst = found.iter().map(|(_, d)| *d).fold(f32::MIN, f32::max);
+                if dist > worst {
+                    break;
+                }
+            }
+
+            let Some(node) = self.nodes.get(&id) else { continue };
+            let Some(neighbors) = node.layers.get(layer) else { continue };
+
+            for neighbor in neighbors.clone() {
+                if visited.insert(neighbor.clone()) {
+                    if let Some(v) = engine.vectors.get(&neighbor) {
+                        let d = self.distance(engine, query, &engine.decode(&v.storage));
+                        frontier.push(Reverse(ScoredId(d, neighbor.clone())));
+                        found.push((neighbor, d));
+                    }
+                }
+            }
+        }
+
+        found.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        found.truncate(ef);
+        found
+    }
+
+    /// Keep a candidate only if it is closer to the new node than to any already-selected
+    /// neighbor, which keeps long-range links instead of collapsing onto the nearest cluster
+    fn select_neighbors(
+        &self,
+        query: &[f32],
+        mut candidates: Vec<(String, f32)>,
+        m: usize,
+        engine: &VectorEngine,
+    ) -> Vec<(String, f32)> {
+        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        let mut selected: Vec<(String, f32)> = Vec::with_capacity(m);
+
+        for (id, dist_to_query) in candidates {
+            if selected.len() >= m {
+                break;
+            }
+
+            let Some(candidate_vec) = engine.vectors.get(&id).map(|e| engine.decode(&e.storage)) else {
+                continue;
+            };
+
+            let keep = selected.iter().all(|(sel_id, _)| {
+                engine.vectors.get(sel_id).map_or(true, |sel| {
+                    dist_to_query < self.distance(engine, &candidate_vec, &engine.decode(&sel.storage))
+                })
+            });
+
+            if keep {
+                selected.push((id, dist_to_query));
+            }
+        }
+
+        selected
+    }
+
+    /// Add a back-link from `from` to `to` at `layer`, pruning to `M` neighbors if needed
+    fn connect(&self, from: &str, to: &str, layer: usize, engine: &VectorEngine) {
+        let Some(mut node) = self.nodes.get_mut(from) else { return };
+        if layer >= node.layers.len() {
+            return;
+        }
+
+        if !node.layers[layer].iter().any(|n| n == to) {
+            node.layers[layer].push(to.to_string());
+        }
+
+        if node.layers[layer].len() > self.params.m {
+            let Some(from_vec) = engine.vectors.get(from).map(|e| engine.decode(&e.storage)) else {
+                return;
+            };
+
+            let mut scored: Vec<(String, f32)> = node.layers[layer]
+                .iter()
+                .filter_map(|n| {
+                    engine
+                        .vectors
+                        .get(n)
+                        .map(|v| (n.clone(), self.distance(engine, &from_vec, &engine.decode(&v.storage))))
+                })
+                .collect();
+
+            scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            scored.truncate(self.params.m);
+            node.layers[layer] = scored.into_iter().map(|(id, _)| id).collect();
+        }
+    }
+
+    /// Greedy descent from the entry point to layer 1, then a beam search at layer 0
+    fn search(&self, query: &[f32], ef: usize, engine: &VectorEngine) -> Vec<(String, f32)> {
+        let Some(entry) = self.entry_point.read().clone() else {
+            return Vec::new();
+        };
+
+        let top_layer = self.nodes.get(&entry).map(|n| n.max_layer).unwrap_or(0);
+        let mut current = entry;
+
+        for layer in (1..=top_layer).rev() {
+            current = self.greedy_search(&current, query, layer, engine);
+        }
+
+        self.search_layer(&current, query, 0, ef, engine)
+    }
+
+    /// Remove a node from the graph and unlink it from every neighbor that referenced it
+    fn remove(&self, id: &str) {
+        self.nodes.remove(id);
+
+        for mut entry in self.nodes.iter_mut() {
+            for layer in entry.layers.iter_mut() {
+                layer.retain(|n| n != id);
+            }
+        }
+
+        let mut entry_point = self.entry_point.write();
+        if entry_point.as_deref() == Some(id) {
+            *entry_point = self
+                .nodes
+                .iter()
+                .max_by_key(|n| n.max_layer)
+                .map(|n| n.key().clone());
         }
     }
 }
@@ -478,6 +1860,7 @@ pub extern "C" fn vector_engine_create(
         default_dimension,
         max_cache_size,
         use_simd,
+        ..EngineConfig::default()
     };
 
     let engine = Box::new(VectorEngine::new(config));
@@ -596,6 +1979,39 @@ pub unsafe extern "C" fn vector_engine_find_similar(
     }
 }
 
+/// Get an approximate latency percentile (e.g. `phi = 0.99` for p99), in nanoseconds
+#[no_mangle]
+pub unsafe extern "C" fn vector_engine_get_latency_percentile(
+    ptr: *mut VectorEnginePtr,
+    phi: c_double,
+) -> c_double {
+    if ptr.is_null() {
+        return -1.0;
+    }
+
+    let wrapper = &*ptr;
+    let engine = &*wrapper.inner;
+
+    engine.get_stats().quantile(phi) as c_double
+}
+
+/// Set the enforced cache-size cap at runtime
+#[no_mangle]
+pub unsafe extern "C" fn vector_engine_set_max_cache_size(
+    ptr: *mut VectorEnginePtr,
+    max_cache_size: usize,
+) -> c_int {
+    if ptr.is_null() {
+        return -1;
+    }
+
+    let wrapper = &*ptr;
+    let engine = &*wrapper.inner;
+
+    engine.set_max_cache_size(max_cache_size);
+    0
+}
+
 /// Free memory allocated for search results
 #[no_mangle]
 pub unsafe extern "C" fn vector_engine_free_results(
@@ -675,4 +2091,165 @@ mod tests {
         assert_eq!(results[0].id, "vec1");
         assert!(results[0].score > results[1].score);
     }
+
+    #[test]
+    fn test_hnsw_find_similar() {
+        let config = EngineConfig {
+            use_hnsw: true,
+            ..EngineConfig::default()
+        };
+        let engine = VectorEngine::new(config);
+
+        engine.insert("vec1".to_string(), vec![1.0, 0.0, 0.0]).unwrap();
+        engine.insert("vec2".to_string(), vec![0.9, 0.1, 0.0]).unwrap();
+        engine.insert("vec3".to_string(), vec![0.0, 1.0, 0.0]).unwrap();
+
+        let query = vec![1.0, 0.0, 0.0];
+        let results = engine.find_similar(&query, 2).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "vec1");
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let mut summary = GkSummary::new(0.01);
+
+        for v in 1..=1000u64 {
+            summary.insert(v);
+        }
+
+        let p50 = summary.quantile(0.5).unwrap();
+        let p99 = summary.quantile(0.99).unwrap();
+
+        assert!(p50 > 400 && p50 < 600);
+        assert!(p99 > 950);
+        assert!(p99 >= p50);
+    }
+
+    #[test]
+    fn test_scalar_quantization_roundtrip() {
+        let config = EngineConfig {
+            storage_mode: StorageMode::Scalar,
+            ..EngineConfig::default()
+        };
+        let engine = VectorEngine::new(config);
+
+        let original = vec![1.0, -2.0, 3.5, 0.0];
+        engine.insert("vec1".to_string(), original.clone()).unwrap();
+
+        let reconstructed = engine.reconstruct("vec1").unwrap();
+        assert_eq!(reconstructed.len(), original.len());
+        for (a, b) in original.iter().zip(reconstructed.iter()) {
+            assert!((a - b).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_product_quantization_trains_after_threshold() {
+        let config = EngineConfig {
+            storage_mode: StorageMode::Product,
+            pq_subvectors: 2,
+            pq_training_threshold: 10,
+            ..EngineConfig::default()
+        };
+        let engine = VectorEngine::new(config);
+
+        for i in 0..10 {
+            let v = vec![i as f32, (i * 2) as f32, (i * 3) as f32, (i * 4) as f32];
+            engine.insert(format!("vec{i}"), v).unwrap();
+        }
+
+        // Codebook should have trained and re-encoded every entry by now
+        let reconstructed = engine.reconstruct("vec0").unwrap();
+        assert_eq!(reconstructed.len(), 4);
+    }
+
+    #[test]
+    fn test_find_similar_ranks_by_distance_under_product_quantization() {
+        let config = EngineConfig {
+            storage_mode: StorageMode::Product,
+            pq_subvectors: 2,
+            pq_training_threshold: 10,
+            ..EngineConfig::default()
+        };
+        let engine = VectorEngine::new(config);
+
+        for i in 0..10 {
+            let v = vec![i as f32, (i * 2) as f32, (i * 3) as f32, (i * 4) as f32];
+            engine.insert(format!("vec{i}"), v).unwrap();
+        }
+
+        // vec9 is the nearest neighbor of the query under every reasonable distance metric, so
+        // it should come back first no matter how the PQ branch scores results internally.
+        let query = vec![9.0, 18.0, 27.0, 36.0];
+        let results = engine.find_similar(&query, 3).unwrap();
+        assert!(!results.is_empty());
+        assert_eq!(results[0].id, "vec9");
+    }
+
+    #[test]
+    fn test_lru_eviction_enforces_max_cache_size() {
+        let config = EngineConfig {
+            max_cache_size: 3,
+            eviction_policy: EvictionPolicy::Lru,
+            ..EngineConfig::default()
+        };
+        let engine = VectorEngine::new(config);
+
+        for i in 0..5 {
+            engine.insert(format!("vec{i}"), vec![i as f32, 0.0, 0.0]).unwrap();
+        }
+
+        assert_eq!(engine.len(), 3);
+        assert_eq!(engine.get_stats().evictions, 2);
+
+        // The oldest entries should be the ones evicted
+        assert!(engine.get("vec0").is_none());
+        assert!(engine.get("vec4").is_some());
+    }
+
+    #[test]
+    fn test_find_similar_batch_cpu_fallback() {
+        let config = EngineConfig {
+            backend: SimilarityBackend::Cpu,
+            ..EngineConfig::default()
+        };
+        let engine = VectorEngine::new(config);
+
+        engine.insert("a".to_string(), vec![1.0, 0.0, 0.0]).unwrap();
+        engine.insert("b".to_string(), vec![0.0, 1.0, 0.0]).unwrap();
+
+        let queries = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+        let results = engine.find_similar_batch(&queries, 1).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0][0].id, "a");
+        assert_eq!(results[1][0].id, "b");
+    }
+
+    #[tokio::test]
+    async fn test_async_vector_engine_mirrors_sync_engine() {
+        let engine = AsyncVectorEngine::new(Arc::new(VectorEngine::new(EngineConfig::default())));
+
+        engine.insert("a".to_string(), vec![1.0, 0.0, 0.0]).await.unwrap();
+        engine.insert("b".to_string(), vec![0.0, 1.0, 0.0]).await.unwrap();
+
+        assert_eq!(engine.len(), 2);
+        assert_eq!(engine.get("a".to_string()).await, Some(vec![1.0, 0.0, 0.0]));
+
+        let results = engine
+            .find_similar(vec![1.0, 0.0, 0.0], 1)
+            .await
+            .unwrap();
+        assert_eq!(results[0].id, "a");
+
+        let mut stream = engine.find_similar_stream(vec![1.0, 0.0, 0.0], 2, 0.0);
+        let first = stream.recv().await.unwrap();
+        assert_eq!(first.id, "a");
+
+        assert!(engine.remove("a".to_string()).await);
+        assert_eq!(engine.len(), 1);
+    }
 }
\ No newline at end of file